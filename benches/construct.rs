@@ -0,0 +1,21 @@
+#![feature(test)]
+
+extern crate test;
+
+use craft_eraser::{ErasedBox, ThinErasedBox};
+use test::{black_box, Bencher};
+
+#[bench]
+fn bench_ebox_new(b: &mut Bencher) {
+    b.iter(|| ErasedBox::new(black_box(5u64)));
+}
+
+#[bench]
+fn bench_ebox_new_slice(b: &mut Bencher) {
+    b.iter(|| ErasedBox::new_array(black_box([1u8, 2, 3])));
+}
+
+#[bench]
+fn bench_thin_new(b: &mut Bencher) {
+    b.iter(|| ThinErasedBox::new(black_box(5u64)));
+}
@@ -0,0 +1,30 @@
+#![feature(test)]
+
+extern crate test;
+
+use craft_eraser::{ErasedBox, ThinErasedBox};
+use test::{black_box, Bencher};
+
+#[bench]
+fn bench_ebox_reify_ref(b: &mut Bencher) {
+    let eb = ErasedBox::new(5u64);
+    b.iter(|| *unsafe { black_box(&eb).reify_ref::<u64>() });
+}
+
+#[bench]
+fn bench_ebox_reify_sized_ref(b: &mut Bencher) {
+    let eb = ErasedBox::new(5u64);
+    b.iter(|| *unsafe { black_box(&eb).reify_sized_ref::<u64>() });
+}
+
+#[bench]
+fn bench_thin_reify_ref(b: &mut Bencher) {
+    let eb = ThinErasedBox::new(5u64);
+    b.iter(|| *unsafe { black_box(&eb).reify_ref::<u64>() });
+}
+
+#[bench]
+fn bench_thin_reify_sized_ref(b: &mut Bencher) {
+    let eb = ThinErasedBox::new(5u64);
+    b.iter(|| *unsafe { black_box(&eb).reify_sized_ref::<u64>() });
+}
@@ -0,0 +1,222 @@
+//! A bump arena for erased values, which are all freed together
+
+use alloc::alloc::{handle_alloc_error, Layout};
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::marker::PhantomData;
+use core::ptr::{self, NonNull};
+use core::{fmt, mem};
+
+use crate::ErasedRef;
+
+/// The size of the first chunk allocated by an arena. Later chunks double in size.
+const INITIAL_CHUNK: usize = 1024;
+
+/// Drops a value in place, given a pointer to it
+type DropFn = unsafe fn(NonNull<()>);
+
+/// # Safety
+///
+/// The pointer must point to a valid `T`, which will not be used again
+unsafe fn drop_value<T>(ptr: NonNull<()>) {
+    ptr::drop_in_place(ptr.cast::<T>().as_ptr());
+}
+
+struct Chunk {
+    data: NonNull<u8>,
+    layout: Layout,
+}
+
+/// An arena storing values of any type in a series of bump-allocated chunks. Values live until
+/// the arena is dropped, at which point their destructors are run, in reverse order of
+/// allocation, and all the memory is freed at once.
+///
+/// Values can't borrow data which is dropped before the arena:
+///
+/// ```compile_fail
+/// # use craft_eraser::ErasedArena;
+/// let arena = ErasedArena::new();
+/// {
+///     let s = String::from("foo");
+///     arena.alloc(s.as_str());
+/// }
+/// drop(arena);
+/// ```
+///
+/// Values are never moved once allocated, as the arena grows by adding chunks rather than
+/// reallocating them. Values may borrow data for `'a`, which must outlive the arena as their
+/// destructors run when it is dropped.
+pub struct ErasedArena<'a> {
+    chunks: RefCell<Vec<Chunk>>,
+    /// The next free byte of the current chunk
+    next: Cell<*mut u8>,
+    /// The number of free bytes left in the current chunk
+    remaining: Cell<usize>,
+    drops: RefCell<Vec<(NonNull<()>, DropFn)>>,
+    /// Invariant, so the arena can't be coerced to a shorter `'a` and then given values which
+    /// don't live as long as it does
+    _phantom: PhantomData<*mut &'a ()>,
+}
+
+impl<'a> ErasedArena<'a> {
+    /// Create a new, empty `ErasedArena`. This doesn't allocate.
+    pub fn new() -> ErasedArena<'a> {
+        ErasedArena {
+            chunks: RefCell::new(Vec::new()),
+            next: Cell::new(ptr::null_mut()),
+            remaining: Cell::new(0),
+            drops: RefCell::new(Vec::new()),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Find space for a value of the provided layout, adding a new chunk if the current one is
+    /// full
+    fn bump(&self, layout: Layout) -> NonNull<u8> {
+        if layout.size() == 0 {
+            // SAFETY: Alignment is always non-zero
+            return unsafe { NonNull::new_unchecked(ptr::without_provenance_mut(layout.align())) };
+        }
+
+        let next = self.next.get();
+        let pad = next.align_offset(layout.align());
+        let remaining = self.remaining.get();
+        if pad <= remaining && layout.size() <= remaining - pad {
+            self.next.set(next.wrapping_add(pad + layout.size()));
+            self.remaining.set(remaining - pad - layout.size());
+            // SAFETY: The pointer is in bounds of a live chunk, so isn't null
+            return unsafe { NonNull::new_unchecked(next.wrapping_add(pad)) };
+        }
+
+        let mut chunks = self.chunks.borrow_mut();
+        let size = chunks
+            .last()
+            .map_or(INITIAL_CHUNK, |chunk| chunk.layout.size() * 2)
+            .max(layout.size());
+        let chunk_layout =
+            Layout::from_size_align(size, layout.align().max(mem::align_of::<usize>()))
+                .expect("Valid size/align pair");
+        // SAFETY: Layout size is non-zero, as it's at least that of a non-zero-sized value
+        let data = NonNull::new(unsafe { alloc::alloc::alloc(chunk_layout) })
+            .unwrap_or_else(|| handle_alloc_error(chunk_layout));
+        chunks.push(Chunk {
+            data,
+            layout: chunk_layout,
+        });
+
+        // The chunk is aligned for the value, so it goes at the start
+        self.next.set(data.as_ptr().wrapping_add(layout.size()));
+        self.remaining.set(size - layout.size());
+        data
+    }
+
+    /// Move a value into this `ErasedArena`, returning an erased reference to it which lives as
+    /// long as the arena is borrowed
+    pub fn alloc<T: 'a>(&self, val: T) -> ErasedRef<'_> {
+        let ptr = self.bump(Layout::new::<T>()).cast::<T>();
+        // SAFETY: `bump` returns space which is valid and aligned for the layout of `T`
+        unsafe { ptr.as_ptr().write(val) };
+        if mem::needs_drop::<T>() {
+            self.drops.borrow_mut().push((ptr.cast(), drop_value::<T>));
+        }
+        // SAFETY: The value was just initialized, and lives until the arena is dropped
+        ErasedRef::new(unsafe { ptr.as_ref() })
+    }
+}
+
+impl Default for ErasedArena<'_> {
+    fn default() -> Self {
+        ErasedArena::new()
+    }
+}
+
+impl fmt::Debug for ErasedArena<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedArena")
+            .field("chunks", &self.chunks.borrow().len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for ErasedArena<'_> {
+    fn drop(&mut self) {
+        for (ptr, drop) in self.drops.get_mut().drain(..).rev() {
+            // SAFETY: Each value is dropped exactly once, with the thunk for its type
+            unsafe { drop(ptr) };
+        }
+        for chunk in self.chunks.get_mut().drain(..) {
+            // SAFETY: Each chunk was allocated with its stored layout
+            unsafe { alloc::alloc::dealloc(chunk.data.as_ptr(), chunk.layout) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+    use alloc::string::String;
+    use alloc::vec;
+
+    #[test]
+    fn test_alloc_mixed() {
+        let arena = ErasedArena::new();
+        let a = arena.alloc(1u8);
+        let b = arena.alloc(String::from("foo"));
+        let c = arena.alloc(2u64);
+        let d = arena.alloc([3u16; 3]);
+        let e = arena.alloc(());
+
+        assert_eq!(*unsafe { a.reify_ref::<u8>() }, 1);
+        assert_eq!(unsafe { b.reify_ref::<String>() }, "foo");
+        assert_eq!(*unsafe { c.reify_ref::<u64>() }, 2);
+        assert_eq!(unsafe { d.reify_ref::<[u16; 3]>() }, &[3; 3]);
+        assert_eq!(unsafe { e.reify_ref::<()>() }, &());
+        assert!(c.as_ptr().is_aligned_for::<u64>());
+    }
+
+    #[test]
+    fn test_chunks() {
+        let arena = ErasedArena::new();
+        let refs = (0..1000u64)
+            .map(|i| arena.alloc(i))
+            .collect::<vec::Vec<_>>();
+        let big = arena.alloc([7u8; 4096]);
+
+        assert!(arena.chunks.borrow().len() > 1);
+        for (i, r) in refs.iter().enumerate() {
+            assert_eq!(*unsafe { r.reify_ref::<u64>() }, i as u64);
+        }
+        assert_eq!(unsafe { big.reify_ref::<[u8; 4096]>() }[4095], 7);
+    }
+
+    #[test]
+    fn test_drop_order() {
+        struct Push(u8, Rc<RefCell<vec::Vec<u8>>>);
+
+        impl Drop for Push {
+            fn drop(&mut self) {
+                self.1.borrow_mut().push(self.0);
+            }
+        }
+
+        let order = Rc::new(RefCell::new(vec::Vec::new()));
+        let arena = ErasedArena::new();
+        arena.alloc(Push(1, order.clone()));
+        arena.alloc(String::from("foo"));
+        arena.alloc(Push(2, order.clone()));
+        arena.alloc(Push(3, order.clone()));
+
+        drop(arena);
+        assert_eq!(*order.borrow(), [3, 2, 1]);
+        assert_eq!(Rc::strong_count(&order), 1);
+    }
+
+    #[test]
+    fn test_borrowed() {
+        let s = String::from("foo");
+        let arena = ErasedArena::new();
+        let r = arena.alloc(s.as_str());
+        assert_eq!(*unsafe { r.reify_ref::<&str>() }, "foo");
+    }
+}
@@ -0,0 +1,71 @@
+//! An extension trait for erasing values without naming the erased type
+
+use core::ptr::Pointee;
+
+use crate::meta::ErasableMeta;
+use crate::thin_ebox::InnerData;
+use crate::{ErasedBox, ErasedPtr, ErasedRef, ThinErasedBox};
+
+/// Methods for erasing any value, implemented for all types. This allows writing
+/// `value.erase_thin()` instead of `ThinErasedBox::new(value)`, and bounding generic code on
+/// `impl AsErased`.
+///
+/// Note that the owning methods erase `self` as a value, so calling them on a `Box<T>` erases the
+/// box itself, not its contents. To adopt the allocation of an existing box, use the `From<Box<T>>`
+/// impls of the erased boxes instead.
+pub trait AsErased: Pointee<Metadata: ErasableMeta> {
+    /// Move this value into a new [`ErasedBox`]
+    fn erase_box(self) -> ErasedBox
+    where
+        Self: Sized,
+    {
+        ErasedBox::new(self)
+    }
+
+    /// Move this value into a new [`ThinErasedBox`]
+    fn erase_thin(self) -> ThinErasedBox
+    where
+        Self: Sized,
+        InnerData<Self>: Pointee<Metadata = Self::Metadata>,
+    {
+        ThinErasedBox::new(self)
+    }
+
+    /// Get an [`ErasedPtr`] to this value
+    fn erase_ptr(&self) -> ErasedPtr {
+        ErasedPtr::new(self)
+    }
+
+    /// Get an [`ErasedRef`] to this value
+    fn erase_ref(&self) -> ErasedRef<'_> {
+        ErasedRef::new(self)
+    }
+}
+
+impl<T: ?Sized + Pointee<Metadata: ErasableMeta>> AsErased for T {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    fn erase_both(val: impl AsErased + Clone) -> (ErasedBox, ThinErasedBox) {
+        (val.clone().erase_box(), val.erase_thin())
+    }
+
+    #[test]
+    fn test_erase_generic() {
+        let (eb, thin) = erase_both(String::from("foo"));
+        assert_eq!(unsafe { eb.reify_ref::<String>() }, "foo");
+        assert_eq!(unsafe { thin.reify_ref::<String>() }, "foo");
+    }
+
+    #[test]
+    fn test_erase_borrowed() {
+        let val: &[u8] = &[1, 2, 3];
+        let ptr = val.erase_ptr();
+        let r = val.erase_ref();
+        assert_eq!(unsafe { &*ptr.reify_ptr::<[u8]>() }, [1, 2, 3]);
+        assert_eq!(unsafe { r.reify_ref::<[u8]>() }, [1, 2, 3]);
+    }
+}
@@ -0,0 +1,74 @@
+//! CRC-32 checksums of erased values, used to detect corruption of long-lived data
+
+use core::ptr::NonNull;
+use core::slice;
+
+/// Lookup table for the reflected CRC-32 (IEEE) polynomial
+const TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// Compute the CRC-32 of a byte slice
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &b| {
+        TABLE[((crc ^ u32::from(b)) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// The checksum of an erased value, along with the number of bytes it covers
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(crate) struct Checksum {
+    len: usize,
+    crc: u32,
+}
+
+impl Checksum {
+    /// Compute the checksum of the `len` bytes at `data`
+    ///
+    /// # Safety
+    ///
+    /// `data` must be valid for reads of `len` bytes, all of which must be initialized
+    pub(crate) unsafe fn compute(data: NonNull<()>, len: usize) -> Checksum {
+        let bytes = slice::from_raw_parts(data.cast::<u8>().as_ptr(), len);
+        Checksum {
+            len,
+            crc: crc32(bytes),
+        }
+    }
+
+    /// Check whether the bytes at `data` still match this checksum
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as [`compute`](Self::compute) apply, for the stored length
+    pub(crate) unsafe fn verify(self, data: NonNull<()>) -> bool {
+        Checksum::compute(data, self.len) == self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+}
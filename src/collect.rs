@@ -0,0 +1,37 @@
+//! Helpers for working with collections of erased values
+
+use core::ptr::Pointee;
+
+use crate::thin_ebox::InnerData;
+use crate::ThinErasedBox;
+
+/// Reify every box in a slice of [`ThinErasedBox`], which all store the same type, as a reference
+/// to that type.
+///
+/// # Safety
+///
+/// The provided `T` must be the same type as originally stored in every box in the slice
+pub unsafe fn reify_slice_refs<'a, T>(boxes: &'a [ThinErasedBox]) -> impl Iterator<Item = &'a T>
+where
+    T: ?Sized + Pointee + 'a,
+    InnerData<T>: Pointee<Metadata = T::Metadata>,
+{
+    // SAFETY: The caller guarantees every box stores a `T`
+    boxes.iter().map(|eb| unsafe { eb.reify_ref::<T>() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_reify_slice_refs() {
+        let boxes: Vec<_> = [1, 2, 3]
+            .into_iter()
+            .map(ThinErasedBox::new::<i32>)
+            .collect();
+        let sum: i32 = unsafe { reify_slice_refs::<i32>(&boxes) }.sum();
+        assert_eq!(sum, 6);
+    }
+}
@@ -1,41 +1,292 @@
 //! A standard erased box implementation, larger but simple implementation
 
+use alloc::alloc::Layout;
 use alloc::boxed::Box;
-use core::ptr::{NonNull, Pointee};
-use core::{fmt, mem};
+use alloc::vec::Vec;
+use core::alloc::AllocError;
+use core::any::{Any, TypeId};
+use core::cell::Cell;
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ptr::{DynMetadata, NonNull, Pointee};
+use core::slice;
+use core::str::{self, Utf8Error};
+use core::{fmt, mem, ptr};
+
+#[cfg(feature = "checksum")]
+use crate::checksum::Checksum;
+use crate::meta::{self, ErasableMeta, MetaKind, RawMeta};
 
 #[inline]
-fn reify_ptr<T: ?Sized + Pointee>(data: NonNull<()>, meta: NonNull<()>) -> NonNull<T> {
-    // SAFETY: Meta will be valid as it came from a `Box::leak` of the correct type call
-    let meta = *unsafe { meta.cast::<T::Metadata>().as_ref() };
+fn reify_ptr<T: ?Sized + Pointee>(data: NonNull<()>, meta: RawMeta) -> NonNull<T> {
+    // SAFETY: Meta will be valid as it was created for the correct type
+    let meta = unsafe { meta.get::<T>() };
     NonNull::<T>::from_raw_parts(data, meta)
 }
 
 #[inline]
-fn reify_box<T: ?Sized + Pointee>(data: NonNull<()>, meta: NonNull<()>) -> Box<T> {
+fn reify_box<T: ?Sized + Pointee>(data: NonNull<()>, meta: RawMeta) -> Box<T> {
     let data = reify_ptr(data, meta);
-    let meta_ptr = meta.cast::<T::Metadata>().as_ptr();
-    // SAFETY: Meta will have come from `Box::leak` of the correct type
-    unsafe { Box::from_raw(meta_ptr) };
     // SAFETY: Data pointer will have come from `Box::leak` of the correct type
     unsafe { Box::from_raw(data.as_ptr()) }
 }
 
-fn drop_erased<T: ?Sized + Pointee>(data: NonNull<()>, meta: NonNull<()>) {
+/// Drops the value of a box and frees its allocation, given its data pointer, metadata, and
+/// vtable
+type DropFn = fn(NonNull<()>, RawMeta, NonNull<BoxVtable>);
+
+fn drop_erased<T: ?Sized + Pointee>(data: NonNull<()>, meta: RawMeta, _: NonNull<BoxVtable>) {
     reify_box::<T>(data, meta);
 }
 
+/// Frees the allocation of a box without dropping its value, given its data pointer, metadata,
+/// and vtable
+type DeallocFn = fn(NonNull<()>, RawMeta, NonNull<BoxVtable>);
+
+fn dealloc_box<T: ?Sized + Pointee>(data: NonNull<()>, meta: RawMeta, _: NonNull<BoxVtable>) {
+    let layout = layout_of::<T>(meta);
+    if layout.size() != 0 {
+        // SAFETY: Data pointer will have come from a `Box<T>`, using the global allocator
+        unsafe { alloc::alloc::dealloc(data.as_ptr().cast(), layout) };
+    }
+}
+
+fn drop_vec<T>(data: NonNull<()>, meta: RawMeta, vtable: NonNull<BoxVtable>) {
+    // SAFETY: Meta will have been created for a `[T]`
+    let len = unsafe { meta.get::<[T]>() };
+    // SAFETY: Boxes created from a `Vec` always have a `VecVtable`
+    let cap = unsafe { vtable.cast::<VecVtable>().as_ref() }.cap;
+    // SAFETY: Data pointer, length and capacity will have come from a `Vec<T>`
+    drop(unsafe { Vec::from_raw_parts(data.cast::<T>().as_ptr(), len, cap) });
+}
+
+fn dealloc_vec<T>(data: NonNull<()>, _: RawMeta, vtable: NonNull<BoxVtable>) {
+    // SAFETY: Boxes created from a `Vec` always have a `VecVtable`
+    let cap = unsafe { vtable.cast::<VecVtable>().as_ref() }.cap;
+    // SAFETY: Data pointer and capacity will have come from a `Vec<T>`, with no values left in it
+    drop(unsafe { Vec::from_raw_parts(data.cast::<T>().as_ptr(), 0, cap) });
+}
+
+fn drop_reinterpreted<T>(data: NonNull<()>, meta: RawMeta, vtable: NonNull<BoxVtable>) {
+    // SAFETY: Meta will have been created for a `[T]`
+    let len = unsafe { meta.get::<[T]>() };
+    let ptr = ptr::slice_from_raw_parts_mut(data.cast::<T>().as_ptr(), len);
+    // SAFETY: The data was reinterpreted as `len` valid values of `T`
+    unsafe { ptr::drop_in_place(ptr) };
+    dealloc_reinterpreted::<T>(data, meta, vtable);
+}
+
+fn dealloc_reinterpreted<T>(data: NonNull<()>, meta: RawMeta, _: NonNull<BoxVtable>) {
+    // SAFETY: Meta will have been created for a `[T]`
+    let size = unsafe { meta.get::<[T]>() } * mem::size_of::<T>();
+    if size != 0 {
+        // SAFETY: Data pointer will have come from a `Box<[u8]>` of `size` bytes
+        unsafe {
+            alloc::alloc::dealloc(
+                data.as_ptr().cast(),
+                Layout::array::<u8>(size).unwrap_unchecked(),
+            )
+        };
+    }
+}
+
+fn drop_custom<T: ?Sized + Pointee>(data: NonNull<()>, meta: RawMeta, vtable: NonNull<BoxVtable>) {
+    // SAFETY: The value is valid until the box is dropped
+    unsafe { ptr::drop_in_place(reify_ptr::<T>(data, meta).as_ptr()) };
+    dealloc_custom(data, meta, vtable);
+}
+
+fn dealloc_custom(data: NonNull<()>, meta: RawMeta, vtable: NonNull<BoxVtable>) {
+    // SAFETY: Boxes created from parts always have a `CustomVtable`
+    let dealloc = unsafe { vtable.cast::<CustomVtable>().as_ref() }.dealloc;
+    dealloc(data, meta)
+}
+
+/// Retrieves the `dyn Any` vtable of a value, given its metadata
+type AnyMetaFn = fn(RawMeta) -> DynMetadata<dyn Any>;
+
+fn any_meta_of<T: Any>(_: RawMeta) -> DynMetadata<dyn Any> {
+    ptr::metadata(ptr::null::<T>() as *const dyn Any)
+}
+
+fn any_meta_stored(meta: RawMeta) -> DynMetadata<dyn Any> {
+    // SAFETY: Only used for boxes storing the metadata of a `dyn Any`
+    unsafe { meta.get::<dyn Any>() }
+}
+
+fn drop_dyn_any(data: NonNull<()>, meta: RawMeta, _: NonNull<BoxVtable>) {
+    let ptr = NonNull::<dyn Any>::from_raw_parts(data, any_meta_stored(meta));
+    // SAFETY: Data will have come from a `Box<dyn Any>` with this vtable
+    drop(unsafe { Box::from_raw(ptr.as_ptr()) });
+}
+
+type CmpFn = fn(NonNull<()>, NonNull<()>) -> Ordering;
+
+fn cmp_erased<T: Ord>(left: NonNull<()>, right: NonNull<()>) -> Ordering {
+    // SAFETY: Only called on boxes with a matching stored `TypeId` of `T`
+    let (left, right) = unsafe { (left.cast::<T>().as_ref(), right.cast::<T>().as_ref()) };
+    left.cmp(right)
+}
+
+/// Computes the layout of the value of a box, given its metadata
+type LayoutFn = fn(RawMeta) -> Layout;
+
+fn layout_of<T: ?Sized + Pointee>(meta: RawMeta) -> Layout {
+    // SAFETY: Meta will have been created for a `T`, which describes a value that fits in memory
+    unsafe { Layout::for_value_raw(ptr::from_raw_parts::<T>(ptr::null::<u8>(), meta.get::<T>())) }
+}
+
+/// Frees the vtable of a box, if it was allocated for that box
+type ReleaseFn = fn(NonNull<BoxVtable>);
+
+fn release_static(_: NonNull<BoxVtable>) {}
+
+fn release_owned<V>(vtable: NonNull<BoxVtable>) {
+    // SAFETY: Owned vtables always come from `Box::leak` of a `V`
+    drop(unsafe { Box::from_raw(vtable.cast::<V>().as_ptr()) });
+}
+
+/// The operations of an [`ErasedBox`] which only depend on the stored type, so may be shared by
+/// every box storing that type rather than stored in each one. Boxes needing extra state, such as
+/// the capacity of a `Vec`, instead allocate their own vtable starting with this one.
+#[repr(C)]
+struct BoxVtable {
+    drop: DropFn,
+    dealloc: DeallocFn,
+    release: ReleaseFn,
+    layout: LayoutFn,
+    meta_kind: MetaKind,
+    /// Whether the value was allocated as a `Box` of the stored type, so its allocation may be
+    /// reused for another value of the same layout
+    boxed: bool,
+    any_meta: Option<AnyMetaFn>,
+    cmp: Option<CmpFn>,
+}
+
+/// The vtable of boxes created from a `Vec`, which also stores its capacity
+#[repr(C)]
+struct VecVtable {
+    base: BoxVtable,
+    cap: usize,
+}
+
+/// The vtable of boxes created from parts, which also stores the caller's deallocation thunk
+#[repr(C)]
+struct CustomVtable {
+    base: BoxVtable,
+    dealloc: fn(NonNull<()>, RawMeta),
+}
+
+/// The vtable of boxes created from a `Box<dyn Any>`, which store its metadata
+static DYN_ANY_VTABLE: BoxVtable = BoxVtable {
+    drop: drop_dyn_any,
+    dealloc: dealloc_box::<dyn Any>,
+    release: release_static,
+    layout: layout_of::<dyn Any>,
+    meta_kind: MetaKind::Dyn,
+    boxed: true,
+    any_meta: Some(any_meta_stored),
+    cmp: None,
+};
+
+/// Holds the static vtables of boxes storing a `T`
+struct VtableFor<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized + Pointee<Metadata: ErasableMeta>> VtableFor<T> {
+    const ERASED: BoxVtable = BoxVtable {
+        drop: drop_erased::<T>,
+        dealloc: dealloc_box::<T>,
+        release: release_static,
+        layout: layout_of::<T>,
+        meta_kind: meta::meta_kind::<T>(),
+        boxed: true,
+        any_meta: None,
+        cmp: None,
+    };
+}
+
+impl<T: Any> VtableFor<T> {
+    const ANY: BoxVtable = BoxVtable {
+        any_meta: Some(any_meta_of::<T>),
+        ..VtableFor::<T>::ERASED
+    };
+}
+
+impl<T: Ord + Any> VtableFor<T> {
+    const ORD: BoxVtable = BoxVtable {
+        cmp: Some(cmp_erased::<T>),
+        ..VtableFor::<T>::ANY
+    };
+}
+
+impl<T> VtableFor<[T]> {
+    const REINTERPRETED: BoxVtable = BoxVtable {
+        drop: drop_reinterpreted::<T>,
+        dealloc: dealloc_reinterpreted::<T>,
+        boxed: false,
+        ..VtableFor::<[T]>::ERASED
+    };
+}
+
+/// The thunk which drops the value of an [`ErasedBox`] and frees its allocation, as returned by
+/// [`ErasedBox::as_raw_parts`] and [`ErasedBox::leak`]
+#[derive(Copy, Clone, Debug)]
+pub struct RawDrop(NonNull<BoxVtable>);
+
+impl RawDrop {
+    /// Drop the value and free the allocation of the box this thunk came from
+    ///
+    /// # Safety
+    ///
+    /// The data pointer and metadata must be those returned alongside this thunk, and the box must
+    /// have been forgotten rather than dropped. This may only be called once.
+    pub unsafe fn call(self, data: NonNull<()>, meta: RawMeta) {
+        let BoxVtable { drop, release, .. } = *self.0.as_ref();
+        drop(data, meta, self.0);
+        release(self.0);
+    }
+}
+
+/// Drop and free the data of an [`ErasedBox`] which was leaked with [`ErasedBox::leak`]
+///
+/// # Safety
+///
+/// The pointer and metadata must have come from a call to `ErasedBox::leak` on a box storing a
+/// `T`, and may not be used again after this call. The box must not have been created with
+/// [`ErasedBox::from_vec`] or [`ErasedBox::from_parts`], as its allocation is freed as a `Box<T>`
+/// with the global allocator. Such boxes must be freed with the [`RawDrop`] returned by `leak`.
+pub unsafe fn drop_erased_raw<T: ?Sized + Pointee>(data: NonNull<()>, meta: RawMeta) {
+    reify_box::<T>(data, meta);
+}
+
+/// The size in bytes of an [`ErasedBox`], for computing storage requirements
+pub const fn fat_box_size() -> usize {
+    mem::size_of::<ErasedBox>()
+}
+
 /// An erased box, storing a (possibly unsized) value of unknown type. Creating one is safe,
 /// but converting it back into any type is unsafe as it requires the user to know the type
 /// stored in the box.
 ///
 /// This box will always be three pointers wide, even for sized types, due to needing to store
-/// an unknown metadata. If you want a box that will always be 1 pointer wide, look at
-/// [`ThinErasedBox`](crate::ThinErasedBox)
+/// an unknown metadata and a vtable for its type. The metadata is stored inline, so only the
+/// value itself is allocated. If you want a box that will always be 1 pointer wide, look at
+/// [`ThinErasedBox`](crate::ThinErasedBox). With the `type_name` feature enabled, the name of the
+/// erased type is also stored, making it five pointers wide. The `checksum` feature adds a
+/// further three pointers, for the optional checksum of the value.
+///
+/// Boxes created from `'static` values with [`new_any`](Self::new_any) additionally track the
+/// [`TypeId`] of the stored value, allowing safe checked access through
+/// [`downcast_ref`](Self::downcast_ref) and [`downcast_mut`](Self::downcast_mut).
 pub struct ErasedBox {
     data: NonNull<()>,
-    meta: NonNull<()>,
-    drop: fn(NonNull<()>, NonNull<()>),
+    meta: RawMeta,
+    vtable: NonNull<BoxVtable>,
+    #[cfg(feature = "type_name")]
+    type_name: &'static str,
+    #[cfg(feature = "checksum")]
+    checksum: Option<Checksum>,
 }
 
 impl ErasedBox {
@@ -44,75 +295,875 @@ impl ErasedBox {
         ErasedBox::from(Box::new(val))
     }
 
+    /// Create a new `ErasedBox` from a `'static` value, tracking its [`TypeId`] so it can later be
+    /// safely downcast
+    pub fn new_any<T: Any>(val: T) -> ErasedBox {
+        let mut out = ErasedBox::new(val);
+        out.vtable = NonNull::from(&VtableFor::<T>::ANY);
+        out
+    }
+
+    /// Create a new `ErasedBox` from a `'static` value, tracking its [`TypeId`] and storing how
+    /// to compare it, allowing ordering with [`type_erased_cmp`](Self::type_erased_cmp)
+    pub fn new_ord<T: Ord + Any>(val: T) -> ErasedBox {
+        let mut out = ErasedBox::new(val);
+        out.vtable = NonNull::from(&VtableFor::<T>::ORD);
+        out
+    }
+
+    /// Attempt to create a new `ErasedBox` from a value, returning the value and an
+    /// [`AllocError`] if the allocation fails instead of aborting.
+    pub fn try_new<T>(val: T) -> Result<ErasedBox, (T, AllocError)> {
+        match Box::<T>::try_new_uninit() {
+            Ok(data) => Ok(ErasedBox::from(Box::write(data, val))),
+            Err(err) => Err((val, err)),
+        }
+    }
+
+    /// Create a new `ErasedBox` from an array, storing it as a slice. The length `N` is kept as
+    /// the slice metadata, so the value may be reified as either `[T; N]` or `[T]` without the
+    /// caller needing to know the length.
+    ///
+    /// Note that [`reify_box`](Self::reify_box) must use `[T]`, as the stored metadata is that of
+    /// a slice.
+    pub fn new_array<T, const N: usize>(val: [T; N]) -> ErasedBox {
+        ErasedBox::from(Box::new(val) as Box<[T]>)
+    }
+
+    /// Create a new `ErasedBox` from a value, storing a checksum of its bytes which can later be
+    /// checked with [`verify`](Self::verify) to detect corruption of the stored value.
+    ///
+    /// The checksum is only taken on creation, so modifying the value through the box will also
+    /// cause verification to fail.
+    ///
+    /// # Safety
+    ///
+    /// `T` must not contain any padding or otherwise uninitialized bytes
+    #[cfg(feature = "checksum")]
+    pub unsafe fn new_checked<T>(val: T) -> ErasedBox {
+        let mut out = ErasedBox::new(val);
+        // SAFETY: The box stores a `T`, which the caller guarantees is fully initialized
+        out.checksum = Some(Checksum::compute(out.data, mem::size_of::<T>()));
+        out
+    }
+
+    /// Create a new `ErasedBox` storing a [`fmt::Write`] implementation as a `dyn fmt::Write`,
+    /// which may later be written to with [`reify_writer`](Self::reify_writer)
+    pub fn new_writer<W: fmt::Write + 'static>(w: W) -> ErasedBox {
+        ErasedBox::from(Box::new(w) as Box<dyn fmt::Write>)
+    }
+
+    /// Create a new `ErasedBox` from a `Box<dyn Any>`, keeping the existing allocation and tracking
+    /// the [`TypeId`] of the contained value so it can be safely downcast.
+    ///
+    /// The box stores the concrete type of the contained value, not `dyn Any`, so it should be
+    /// reified as that type.
+    ///
+    /// Note that `ErasedBox::from` also accepts a `Box<dyn Any>`, but treats it like any other
+    /// `Box`: the value is stored as a `dyn Any` and its type isn't tracked, so
+    /// [`downcast_ref`](Self::downcast_ref) and [`type_id`](Self::type_id) will always fail. Use
+    /// this function to keep the type.
+    pub fn from_any(val: Box<dyn Any>) -> ErasedBox {
+        let (data, meta) = NonNull::from(Box::leak(val)).to_raw_parts();
+
+        ErasedBox {
+            data,
+            meta: RawMeta::new::<dyn Any>(meta),
+            vtable: NonNull::from(&DYN_ANY_VTABLE),
+            #[cfg(feature = "type_name")]
+            type_name: core::any::type_name::<dyn Any>(),
+            #[cfg(feature = "checksum")]
+            checksum: None,
+        }
+    }
+
     /// Create a new `ErasedBox` from an existing `Box`
     pub fn from_box<T>(val: Box<T>) -> ErasedBox {
         ErasedBox::from(val)
     }
 
-    /// Create a new `ErasedBox` from a pointer to an existing allocation
+    /// Create a new `ErasedBox` from a `Vec`, storing it as a slice while keeping any spare
+    /// capacity of the allocation.
+    ///
+    /// The value may be reified as a `[T]` by reference, but must be converted back with
+    /// [`reify_vec`](Self::reify_vec) rather than [`reify_box`](Self::reify_box), as the
+    /// allocation may be larger than the slice. The capacity is kept in a small vtable allocated
+    /// for the box, as it can't be shared with other boxes.
+    pub fn from_vec<T>(val: Vec<T>) -> ErasedBox {
+        let mut val = ManuallyDrop::new(val);
+        // SAFETY: The pointer of a `Vec` is never null
+        let data = unsafe { NonNull::new_unchecked(val.as_mut_ptr()) }.cast::<()>();
+        let vtable = Box::new(VecVtable {
+            base: BoxVtable {
+                drop: drop_vec::<T>,
+                dealloc: dealloc_vec::<T>,
+                release: release_owned::<VecVtable>,
+                boxed: false,
+                ..VtableFor::<[T]>::ERASED
+            },
+            cap: val.capacity(),
+        });
+
+        ErasedBox {
+            data,
+            meta: RawMeta::new::<[T]>(val.len()),
+            vtable: NonNull::from(Box::leak(vtable)).cast(),
+            #[cfg(feature = "type_name")]
+            type_name: core::any::type_name::<[T]>(),
+            #[cfg(feature = "checksum")]
+            checksum: None,
+        }
+    }
+
+    /// Create a new `ErasedBox` from a pointer to an existing allocation
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be valid, and the allocation should match that which can later be passed
+    /// to `Box::from_raw`
+    pub unsafe fn from_raw<T: ?Sized + Pointee<Metadata: ErasableMeta>>(
+        val: NonNull<T>,
+    ) -> ErasedBox {
+        let (data, meta) = val.to_raw_parts();
+
+        ErasedBox {
+            data,
+            meta: RawMeta::new::<T>(meta),
+            vtable: NonNull::from(&VtableFor::<T>::ERASED),
+            #[cfg(feature = "type_name")]
+            type_name: core::any::type_name::<T>(),
+            #[cfg(feature = "checksum")]
+            checksum: None,
+        }
+    }
+
+    /// Create a new `ErasedBox` from a pointer to an allocation of any kind, with a custom
+    /// deallocation thunk. Unlike [`from_raw`](Self::from_raw), the allocation doesn't need to
+    /// come from a `Box`, such as for memory-mapped or foreign storage. The thunk is called with
+    /// the data pointer and the metadata whenever the box frees its allocation, after the value
+    /// has been dropped or moved out, such as when the box is dropped or reified with
+    /// [`reify_value`](Self::reify_value). It must only free the allocation. The thunk is kept in
+    /// a small vtable allocated for the box.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be valid and aligned for a `T`, with valid metadata, until the box is
+    /// dropped. The value must not be accessed other than through the box in that time. The box
+    /// must not be reified as a `Box`, with [`reify_box`](Self::reify_box) or
+    /// [`into_uninit_box`](Self::into_uninit_box), or passed to [`drop_erased_raw`], as these free
+    /// the allocation with the global allocator.
+    pub unsafe fn from_parts<T: ?Sized + Pointee<Metadata: ErasableMeta>>(
+        data: NonNull<()>,
+        meta: T::Metadata,
+        dealloc: fn(NonNull<()>, RawMeta),
+    ) -> ErasedBox {
+        let vtable = Box::new(CustomVtable {
+            base: BoxVtable {
+                drop: drop_custom::<T>,
+                dealloc: dealloc_custom,
+                release: release_owned::<CustomVtable>,
+                boxed: false,
+                ..VtableFor::<T>::ERASED
+            },
+            dealloc,
+        });
+
+        ErasedBox {
+            data,
+            meta: RawMeta::new::<T>(meta),
+            vtable: NonNull::from(Box::leak(vtable)).cast(),
+            #[cfg(feature = "type_name")]
+            type_name: core::any::type_name::<T>(),
+            #[cfg(feature = "checksum")]
+            checksum: None,
+        }
+    }
+
+    /// Get the raw pointer to the contained data
+    pub fn raw_ptr(&self) -> NonNull<()> {
+        self.data
+    }
+
+    /// Get the raw pointer to the meta of the contained data. The metadata is stored inline, so
+    /// this points into the box itself.
+    pub fn raw_meta_ptr(&self) -> NonNull<()> {
+        self.meta.as_ptr()
+    }
+
+    /// Get all the raw components of this `ErasedBox` at once, without consuming it: the data
+    /// pointer, the metadata, and the thunk which drops the value and frees the allocation.
+    ///
+    /// The returned pointer is only valid while the box lives, and calling the thunk on the parts
+    /// of a box which is still live, or is later dropped, is a double free.
+    pub fn as_raw_parts(&self) -> (NonNull<()>, RawMeta, RawDrop) {
+        (self.data, self.meta, RawDrop(self.vtable))
+    }
+
+    fn vtable(&self) -> &BoxVtable {
+        // SAFETY: The vtable is either static, or allocated for and owned by this box
+        unsafe { self.vtable.as_ref() }
+    }
+
+    /// Get the layout of the value stored in this `ErasedBox`, as recorded in its vtable
+    fn value_layout(&self) -> Layout {
+        (self.vtable().layout)(self.meta)
+    }
+
+    /// Free the allocation of this `ErasedBox` through its vtable, without dropping the value
+    ///
+    /// # Safety
+    ///
+    /// The value must have been moved out or dropped already
+    unsafe fn dealloc(self) {
+        (self.vtable().dealloc)(self.data, self.meta, self.vtable);
+        // Skip Drop call to avoid dropping the moved-out data
+        self.release();
+    }
+
+    /// Check whether the data pointer of this `ErasedBox` is aligned for a `T`. This is a cheap check
+    /// to make before reifying.
+    pub fn is_aligned_for<T>(&self) -> bool {
+        self.data.cast::<T>().is_aligned()
+    }
+
+    fn any_ptr(&self) -> Option<NonNull<dyn Any>> {
+        let meta = (self.vtable().any_meta?)(self.meta);
+        Some(NonNull::from_raw_parts(self.data, meta))
+    }
+
+    fn as_any(&self) -> Option<&dyn Any> {
+        // SAFETY: The box stores a value of the type the vtable was created for
+        self.any_ptr().map(|ptr| unsafe { ptr.as_ref() })
+    }
+
+    /// Convert this `ErasedBox` into a `Box<dyn Any>`, if it tracks the type of its value. Boxes
+    /// created from non-`'static` values, or without tracking, are returned unchanged as an error.
+    pub fn into_any(self) -> Result<Box<dyn Any>, ErasedBox> {
+        match self.any_ptr() {
+            Some(ptr) => {
+                self.release();
+                // SAFETY: The data pointer will have come from a `Box` of the tracked type
+                Ok(unsafe { Box::from_raw(ptr.as_ptr()) })
+            }
+            None => Err(self),
+        }
+    }
+
+    /// Get the [`TypeId`] of the value stored in this `ErasedBox`, if it was created from a
+    /// `'static` type with tracking
+    pub fn type_id(&self) -> Option<TypeId> {
+        self.as_any().map(<dyn Any>::type_id)
+    }
+
+    /// Get the name of the type stored in this `ErasedBox`, for debugging purposes. This is only
+    /// recorded with the `type_name` feature enabled, and returns `None` otherwise.
+    ///
+    /// The name is that of the type originally erased, as given by [`core::any::type_name`], so it
+    /// has the same caveats. Boxes created with [`from_any`](Self::from_any) record `dyn Any`.
+    pub fn type_name(&self) -> Option<&'static str> {
+        #[cfg(feature = "type_name")]
+        return Some(self.type_name);
+        #[cfg(not(feature = "type_name"))]
+        return None;
+    }
+
+    /// Recompute the checksum of the value stored in this `ErasedBox`, returning whether it still
+    /// matches the one stored when it was created with [`new_checked`](Self::new_checked). Boxes
+    /// created without a checksum always pass.
+    #[cfg(feature = "checksum")]
+    pub fn verify(&self) -> bool {
+        match self.checksum {
+            // SAFETY: The checksum was computed over the fully initialized bytes of our value
+            Some(checksum) => unsafe { checksum.verify(self.data) },
+            None => true,
+        }
+    }
+
+    /// Check the invariants of this `ErasedBox` which can be verified without knowing the stored
+    /// type, as a diagnostic for memory corruption. The kind of metadata and layout of the value
+    /// recorded in the vtable are checked against the stored metadata and data pointer. Passing
+    /// doesn't guarantee the box is valid.
+    ///
+    /// # Panics
+    ///
+    /// If the recorded metadata kind is unknown, the metadata is invalid for its kind, or the data
+    /// pointer is misaligned or too high for the recorded layout
+    pub fn assert_valid(&self) {
+        // SAFETY: The vtable is valid to read. The kind is read as a raw byte, so a corrupted
+        //         value can be detected.
+        let kind = unsafe {
+            ptr::addr_of!((*self.vtable.as_ptr()).meta_kind)
+                .cast::<u8>()
+                .read()
+        };
+        let kind = MetaKind::from_raw(kind).expect("ErasedBox metadata kind is unknown");
+        assert!(
+            kind.is_valid(self.meta),
+            "ErasedBox metadata is invalid for its kind"
+        );
+
+        let layout = self.value_layout();
+        let addr = self.data.as_ptr().addr();
+        assert!(
+            addr.is_multiple_of(layout.align()),
+            "ErasedBox data pointer is misaligned"
+        );
+        assert!(
+            addr.checked_add(layout.size()).is_some(),
+            "ErasedBox value extends past the end of the address space"
+        );
+    }
+
+    /// Check whether the value stored in this `ErasedBox` is of type `T`. Returns `false` if the
+    /// box doesn't track its type.
+    pub fn is<T: Any>(&self) -> bool {
+        self.type_id() == Some(TypeId::of::<T>())
+    }
+
+    /// Get a reference to the value stored in this `ErasedBox` if it is of type `T`, or `None`
+    /// if the type doesn't match or the box doesn't track its type.
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        if self.is::<T>() {
+            // SAFETY: We just checked the stored type matches
+            Some(unsafe { self.reify_ref() })
+        } else {
+            None
+        }
+    }
+
+    /// Get a mutable reference to the value stored in this `ErasedBox` if it is of type `T`, or
+    /// `None` if the type doesn't match or the box doesn't track its type.
+    pub fn downcast_mut<T: Any>(&mut self) -> Option<&mut T> {
+        if self.is::<T>() {
+            // SAFETY: We just checked the stored type matches
+            Some(unsafe { self.reify_mut() })
+        } else {
+            None
+        }
+    }
+
+    /// Get a reference to the value stored in this `ErasedBox` along with its [`TypeId`], if it is
+    /// of type `T`. This is useful for logging which type was accessed.
+    pub fn reify_ref_with_type<T: Any>(&self) -> Option<(&T, TypeId)> {
+        let type_id = self.type_id()?;
+        self.downcast_ref().map(|val| (val, type_id))
+    }
+
+    /// Check whether this `ErasedBox` and another store values of the same type, if it can be
+    /// determined.
+    ///
+    /// If both boxes track their type, this compares the [`TypeId`]s and is exact. Otherwise, boxes
+    /// sharing the same drop function are assumed to store the same type. This is a heuristic:
+    /// the compiler may merge identical drop functions of different types, such as `u32` and
+    /// `i32`, so a `Some(true)` from it should not be relied upon for soundness. Returns `None` if
+    /// neither check applies.
+    pub fn same_type(&self, other: &ErasedBox) -> Option<bool> {
+        if let (Some(left), Some(right)) = (self.type_id(), other.type_id()) {
+            Some(left == right)
+        } else if ptr::fn_addr_eq(self.vtable().drop, other.vtable().drop) {
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    /// Compare the values stored in this `ErasedBox` and another. Returns `None` if either box
+    /// doesn't track its type, the types differ, or neither box was created with
+    /// [`new_ord`](Self::new_ord).
+    pub fn type_erased_cmp(&self, other: &ErasedBox) -> Option<Ordering> {
+        if self.type_id()? != other.type_id()? {
+            return None;
+        }
+        // Both boxes store the same type, so either comparison may be used
+        let cmp = self.vtable().cmp.or(other.vtable().cmp)?;
+        Some(cmp(self.data, other.data))
+    }
+
+    /// Get a pointer to the value stored in this `ErasedBox`
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_ptr<T: ?Sized>(&self) -> NonNull<T> {
+        reify_ptr(self.data, self.meta)
+    }
+
+    /// Convert an `ErasedBox` back into a [`Box`] of the provided type
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box. The box must not
+    /// have been created with [`from_parts`](Self::from_parts), as the `Box` frees the allocation
+    /// with the global allocator.
+    pub unsafe fn reify_box<T: ?Sized + Pointee>(self) -> Box<T> {
+        // Skip Drop call to avoid dropping the moved-out data
+        let (data, meta) = self.release();
+        reify_box(data, meta)
+    }
+
+    /// Convert an `ErasedBox` created with [`from_vec`](Self::from_vec) back into a [`Vec`],
+    /// including any spare capacity
+    ///
+    /// # Safety
+    ///
+    /// The box must have been created by `from_vec` with a `Vec<T>` of the provided `T`
+    pub unsafe fn reify_vec<T>(self) -> Vec<T> {
+        let len = self.meta.get::<[T]>();
+        let data = self.data.cast::<T>().as_ptr();
+        let cap = self.vtable.cast::<VecVtable>().as_ref().cap;
+        // Skip Drop call to avoid dropping the moved-out data
+        self.release();
+        // SAFETY: Data pointer, length and capacity will have come from a `Vec<T>`
+        Vec::from_raw_parts(data, len, cap)
+    }
+
+    /// Get how many more `T` elements fit in the allocation backing this `ErasedBox`, beyond the
+    /// current length
+    ///
+    /// # Safety
+    ///
+    /// The box must have been created by [`from_vec`](Self::from_vec) with a `Vec<T>` of the
+    /// provided `T`
+    pub unsafe fn spare_capacity<T>(&self) -> usize {
+        self.vtable.cast::<VecVtable>().as_ref().cap - self.meta.get::<[T]>()
+    }
+
+    /// Move the value stored in this `ErasedBox` out, freeing the backing allocation without
+    /// running any destructor on the moved-out value.
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_value<T>(self) -> T {
+        let val = self.data.cast::<T>().as_ptr().read();
+        // SAFETY: The value was just moved out
+        self.dealloc();
+        val
+    }
+
+    /// Move a `Vec<T>` stored in this `ErasedBox` out as a boxed slice, freeing the backing
+    /// allocation of the box. Any spare capacity of the vector is discarded.
+    ///
+    /// This is for boxes storing the `Vec` itself, such as those created with
+    /// [`new`](Self::new). Boxes created with [`from_vec`](Self::from_vec) should use
+    /// [`reify_vec`](Self::reify_vec) instead.
+    ///
+    /// # Safety
+    ///
+    /// The box must store a `Vec<T>` of the provided `T`
+    pub unsafe fn reify_into_boxed_slice<T>(self) -> Box<[T]> {
+        self.reify_value::<Vec<T>>().into_boxed_slice()
+    }
+
+    /// Move the value stored in this `ErasedBox` out into the provided slot, freeing the backing
+    /// allocation. Any value already in the slot is dropped.
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_into_slot<T>(self, slot: &mut Option<T>) {
+        *slot = Some(self.reify_value());
+    }
+
+    /// Move the value stored in this `ErasedBox` out, and erase a new value in its place. If the
+    /// layouts of `T` and `U` match, and the value was allocated as a `Box`, the allocation is
+    /// reused for the new value.
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_replace<T, U>(self, new: U) -> (T, ErasedBox) {
+        if !self.vtable().boxed || Layout::new::<T>() != Layout::new::<U>() {
+            return (self.reify_value(), ErasedBox::new(new));
+        }
+
+        let data = self.data.cast::<T>();
+        let old = data.as_ptr().read();
+        let data = data.cast::<U>();
+        // SAFETY: The allocation is valid for a `U`, as it has the same layout as a `T`
+        data.as_ptr().write(new);
+
+        // Skip Drop call, as the allocation is reused for the new box
+        self.release();
+        // SAFETY: The data pointer came from a `Box<T>`, which may be freed as a `Box<U>` with the
+        //         same layout
+        (old, ErasedBox::from_raw(data))
+    }
+
+    /// Move the value stored in this `ErasedBox` out, wrapped in a [`ManuallyDrop`] so the caller
+    /// controls when (or if) it is dropped. The backing allocation is freed.
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_manually<T>(self) -> ManuallyDrop<T> {
+        ManuallyDrop::new(self.reify_value())
+    }
+
+    /// Drop the value stored in this `ErasedBox`, returning its allocation as uninitialized storage
+    /// for a new `T`
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box. The box must not
+    /// have been created with [`from_parts`](Self::from_parts), as the `Box` frees the allocation
+    /// with the global allocator.
+    pub unsafe fn into_uninit_box<T>(self) -> Box<MaybeUninit<T>> {
+        debug_assert!(self.vtable().boxed, "box was not allocated as a `Box`");
+        let data = self.data.cast::<T>();
+        ptr::drop_in_place(data.as_ptr());
+
+        // Skip Drop call to avoid dropping the already dropped data
+        self.release();
+        // SAFETY: Data pointer will have come from a `Box<T>`, which has the same layout as a
+        //         `Box<MaybeUninit<T>>`
+        Box::from_raw(data.cast::<MaybeUninit<T>>().as_ptr())
+    }
+
+    /// Map the value stored in this `ErasedBox` to a new value, returning a new box storing the
+    /// result. The original allocation is freed.
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn map<T, U>(self, f: impl FnOnce(T) -> U) -> ErasedBox {
+        ErasedBox::new(f(self.reify_value()))
+    }
+
+    /// Map the value stored in this `ErasedBox` to a new value if it is of type `T`, returning a
+    /// new box tracking the type `U`. If the type doesn't match, or the box doesn't track its
+    /// type, the box is returned unchanged.
+    pub fn try_map<T: Any, U: Any>(self, f: impl FnOnce(T) -> U) -> Result<ErasedBox, ErasedBox> {
+        if self.is::<T>() {
+            // SAFETY: We just checked the stored type matches
+            let val = unsafe { self.reify_value::<T>() };
+            Ok(ErasedBox::new_any(f(val)))
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Leak this `ErasedBox`, returning the pointer to its data, its metadata, and the thunk which
+    /// drops the value and frees the allocation, without running any destructor. The allocation is
+    /// owned by the caller afterwards, and will never be freed unless the thunk is called with the
+    /// returned parts.
+    ///
+    /// Boxes which were created from a `Box`, rather than [`from_vec`](Self::from_vec) or
+    /// [`from_parts`](Self::from_parts), may also be freed by passing the parts to
+    /// [`drop_erased_raw`] with the type originally stored in the box.
+    pub fn leak(self) -> (NonNull<()>, RawMeta, RawDrop) {
+        let this = ManuallyDrop::new(self);
+        (this.data, this.meta, RawDrop(this.vtable))
+    }
+
+    /// Release the vtable of this `ErasedBox` if it owns one, returning the pointer to its data and
+    /// its metadata without dropping the value or freeing the allocation
+    fn release(self) -> (NonNull<()>, RawMeta) {
+        let this = ManuallyDrop::new(self);
+        (this.vtable().release)(this.vtable);
+        (this.data, this.meta)
+    }
+
+    /// Swap this `ErasedBox` with another, exchanging their allocations. As each box fully
+    /// describes its own allocation, this works no matter the types stored in either box.
+    pub fn swap(&mut self, other: &mut ErasedBox) {
+        mem::swap(self, other)
+    }
+
+    /// Replace this `ErasedBox` with another, returning the previous box. Like [`swap`](Self::swap)
+    /// this works no matter the types stored in either box.
+    pub fn replace(&mut self, new: ErasedBox) -> ErasedBox {
+        mem::replace(self, new)
+    }
+
+    /// Swap the values stored in this `ErasedBox` and another, without moving either allocation
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in both boxes
+    pub unsafe fn swap_values<T>(&mut self, other: &mut ErasedBox) {
+        mem::swap(self.reify_mut::<T>(), other.reify_mut::<T>());
+        #[cfg(feature = "checksum")]
+        mem::swap(&mut self.checksum, &mut other.checksum);
+    }
+
+    /// Get a reference to the value stored in this `ErasedBox`
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_ref<T: ?Sized>(&self) -> &T {
+        self.reify_ptr().as_ref()
+    }
+
+    /// Get a reference to the value stored in this `ErasedBox`, if it passes some cheap sanity
+    /// checks: the data must be aligned for `T`, and the size of `T` with the stored metadata must
+    /// match that of the stored value. This catches mistakes such as a
+    /// wrongly-sized struct or slice element, even for non-`'static` types which can't be checked
+    /// by [`TypeId`].
+    ///
+    /// This is only a heuristic. A different type of the same layout will pass the checks, so it
+    /// is still up to the caller to provide the right type.
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box. At minimum, its
+    /// metadata must be of the same kind as that stored, so its size and alignment can be computed.
+    pub unsafe fn try_reify_ref<T: ?Sized>(&self) -> Option<&T> {
+        let ptr = self.reify_ptr::<T>();
+        if !ptr
+            .addr()
+            .get()
+            .is_multiple_of(mem::align_of_val_raw(ptr.as_ptr()))
+        {
+            return None;
+        }
+        if mem::size_of_val_raw(ptr.as_ptr()) != self.value_layout().size() {
+            return None;
+        }
+        Some(ptr.as_ref())
+    }
+
+    /// View the value stored in this `ErasedBox` as its raw bytes, such as for hashing or
+    /// serializing plain data. The size of the value is taken from the box's vtable, so the type
+    /// doesn't need to be known.
+    ///
+    /// # Safety
+    ///
+    /// All bytes of the stored value must be initialized. This isn't the case for types with
+    /// padding, whose padding bytes are uninitialized, so this is only sound for plain data types
+    /// without any.
+    pub unsafe fn as_bytes(&self) -> &[u8] {
+        slice::from_raw_parts(self.data.cast::<u8>().as_ptr(), self.value_layout().size())
+    }
+
+    /// Get a reference to the value stored in this `ErasedBox` using the provided metadata,
+    /// ignoring the stored metadata entirely. This is the lowest level way to reify a box.
+    ///
+    /// # Safety
+    ///
+    /// The stored value must be valid to read as a `T` with the provided metadata
+    pub unsafe fn reify_ref_with<T: ?Sized + Pointee>(&self, meta: T::Metadata) -> &T {
+        NonNull::<T>::from_raw_parts(self.data, meta).as_ref()
+    }
+
+    /// Get a copy of the value in the [`Cell`] stored in this `ErasedBox`
+    ///
+    /// # Safety
+    ///
+    /// The value originally stored in the box must be a `Cell<T>`
+    pub unsafe fn reify_cell_get<T: Copy>(&self) -> T {
+        self.reify_ref::<Cell<T>>().get()
+    }
+
+    /// Get a reference to the sized value stored in this `ErasedBox`. Unlike
+    /// [`reify_ref`](Self::reify_ref), this never reads the stored metadata.
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_sized_ref<T>(&self) -> &T {
+        self.data.cast::<T>().as_ref()
+    }
+
+    /// Get a reference to the array stored in this `ErasedBox`. In debug builds, the size of the
+    /// stored value is checked against that of a `[T; N]`.
+    ///
+    /// # Safety
+    ///
+    /// The value originally stored in the box must be a `[T; N]`, or a `[T]` of length `N`
+    pub unsafe fn reify_array_ref<T, const N: usize>(&self) -> &[T; N] {
+        debug_assert_eq!(
+            self.value_layout().size(),
+            mem::size_of::<[T; N]>(),
+            "stored value is not the size of the requested array"
+        );
+        self.data.cast::<[T; N]>().as_ref()
+    }
+
+    /// Get a reference to the sized value stored in this `ErasedBox` as a trait object, using the
+    /// provided vtable. The vtable can be retrieved when erasing with
+    /// [`ptr::metadata`], such as `ptr::metadata(&val as &dyn Trait)`.
+    ///
+    /// # Safety
+    ///
+    /// The box must store a sized value, and `meta` must be the vtable of that value's type for
+    /// `Dyn`
+    pub unsafe fn reify_dyn<Dyn>(&self, meta: DynMetadata<Dyn>) -> &Dyn
+    where
+        Dyn: ?Sized + Pointee<Metadata = DynMetadata<Dyn>>,
+    {
+        NonNull::<Dyn>::from_raw_parts(self.data, meta).as_ref()
+    }
+
+    /// Get a reference to the slice stored in this `ErasedBox`, using the stored length. In debug
+    /// builds, this checks the size of the slice matches that of the stored value, which catches
+    /// most mistakes in the element type.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, if the size of the requested slice doesn't match the stored value
+    ///
+    /// # Safety
+    ///
+    /// The box must store a `[T]`
+    pub unsafe fn reify_slice<T>(&self) -> &[T] {
+        let len = self.meta.get::<[T]>();
+        debug_assert_eq!(
+            len.wrapping_mul(mem::size_of::<T>()),
+            self.value_layout().size(),
+            "stored value is not the size of the requested slice"
+        );
+        slice::from_raw_parts(self.data.cast::<T>().as_ptr(), len)
+    }
+
+    /// Get a reference to the value stored in this `ErasedBox` as a slice of the provided length,
+    /// ignoring any stored metadata. This is intended for cases where the length is stored
+    /// separately from the data, and the metadata wasn't preserved when erasing.
+    ///
+    /// # Safety
+    ///
+    /// The stored value must be valid to read as `len` consecutive, correctly aligned, values of
+    /// type `T`
+    pub unsafe fn reify_slice_with_len<T>(&self, len: usize) -> &[T] {
+        slice::from_raw_parts(self.data.cast::<T>().as_ptr(), len)
+    }
+
+    /// Get two references to the slice stored in this `ErasedBox`, split at the provided index
+    ///
+    /// # Panics
+    ///
+    /// If `mid` is greater than the stored length
+    ///
+    /// # Safety
+    ///
+    /// The value originally stored in the box must be a `[T]`
+    pub unsafe fn reify_split_at<T>(&self, mid: usize) -> (&[T], &[T]) {
+        self.reify_ref::<[T]>().split_at(mid)
+    }
+
+    /// Convert this `ErasedBox` back into a `Box<str>`
+    ///
+    /// # Safety
+    ///
+    /// The value originally stored in the box must be a `str`
+    pub unsafe fn reify_boxed_str(self) -> Box<str> {
+        self.reify_box::<str>()
+    }
+
+    /// Get a mutable reference to the closure stored in this `ErasedBox`
+    ///
+    /// # Safety
+    ///
+    /// The value originally stored in the box must be a `dyn FnMut()`
+    pub unsafe fn reify_fn_mut(&mut self) -> &mut dyn FnMut() {
+        self.reify_mut::<dyn FnMut()>()
+    }
+
+    /// Call the closure stored in this `ErasedBox`, consuming the box
     ///
     /// # Safety
     ///
-    /// The pointer must be valid, and the allocation should match that which can later be passed
-    /// to `Box::from_raw`
-    pub unsafe fn from_raw<T: ?Sized>(val: NonNull<T>) -> ErasedBox {
-        let (data, meta) = val.to_raw_parts();
-        let meta = NonNull::from(Box::leak(Box::new(meta))).cast::<()>();
-
-        ErasedBox {
-            data,
-            meta,
-            drop: drop_erased::<T>,
-        }
+    /// The value originally stored in the box must be a `dyn FnOnce()`
+    pub unsafe fn invoke_once(self) {
+        self.reify_box::<dyn FnOnce()>()()
     }
 
-    /// Get the raw pointer to the contained data
-    pub fn raw_ptr(&self) -> NonNull<()> {
-        self.data
+    /// Get a mutable reference to the writer stored in this `ErasedBox`
+    ///
+    /// # Safety
+    ///
+    /// The value originally stored in the box must be a `dyn fmt::Write`, such as one created by
+    /// [`new_writer`](Self::new_writer)
+    pub unsafe fn reify_writer(&mut self) -> &mut dyn fmt::Write {
+        self.reify_mut::<dyn fmt::Write>()
     }
 
-    /// Get the raw pointer to the meta of the contained data
-    pub fn raw_meta_ptr(&self) -> NonNull<()> {
-        self.meta
+    /// Get a reference to the bytes stored in this `ErasedBox` as a `str`, validating that they
+    /// are UTF-8 first.
+    ///
+    /// # Safety
+    ///
+    /// The value originally stored in the box must be a `[u8]` or `str`
+    pub unsafe fn reify_str_checked(&self) -> Result<&str, Utf8Error> {
+        str::from_utf8(self.reify_ref::<[u8]>())
     }
 
-    /// Get a pointer to the value stored in this `ErasedBox`
+    /// Get a mutable reference to the value stored in this `ErasedBox`
     ///
     /// # Safety
     ///
     /// The provided `T` must be the same type as originally stored in the box
-    pub unsafe fn reify_ptr<T: ?Sized>(&self) -> NonNull<T> {
-        reify_ptr(self.data, self.meta)
+    pub unsafe fn reify_mut<T: ?Sized>(&mut self) -> &mut T {
+        self.reify_ptr().as_mut()
     }
 
-    /// Convert an `ErasedBox` back into a [`Box`] of the provided type
+    /// Call a closure with a reference to the value stored in this `ErasedBox`. Unlike
+    /// [`reify_ref`](Self::reify_ref), the reference can't escape the closure, so it's clear it
+    /// never outlives the box.
     ///
     /// # Safety
     ///
     /// The provided `T` must be the same type as originally stored in the box
-    pub unsafe fn reify_box<T: ?Sized + Pointee>(self) -> Box<T> {
-        let data = reify_box(self.data, self.meta);
-        // Skip Drop call to avoid dropping the moved-out data
-        mem::forget(self);
-        data
+    pub unsafe fn with_ref<T: ?Sized, R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(self.reify_ref())
     }
 
-    /// Get a reference to the value stored in this `ErasedBox`
+    /// Call a closure with a mutable reference to the value stored in this `ErasedBox`. Unlike
+    /// [`reify_mut`](Self::reify_mut), the reference can't escape the closure, so it's clear it
+    /// never outlives the box.
     ///
     /// # Safety
     ///
     /// The provided `T` must be the same type as originally stored in the box
-    pub unsafe fn reify_ref<T: ?Sized>(&self) -> &T {
-        self.reify_ptr().as_ref()
+    pub unsafe fn with_mut<T: ?Sized, R>(&mut self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(self.reify_mut())
     }
 
-    /// Get a mutable reference to the value stored in this `ErasedBox`
+    /// Get a mutable reference to the storage of the value in this `ErasedBox`, as possibly
+    /// uninitialized memory. This allows re-initializing the value after it has been manually
+    /// dropped.
     ///
     /// # Safety
     ///
-    /// The provided `T` must be the same type as originally stored in the box
-    pub unsafe fn reify_mut<T: ?Sized>(&mut self) -> &mut T {
-        self.reify_ptr().as_mut()
+    /// The provided `T` must be the same type as originally stored in the box. The value must be
+    /// initialized again before the box is dropped or reified as `T`.
+    pub unsafe fn reify_uninit_mut<T>(&mut self) -> &mut MaybeUninit<T> {
+        self.data.cast::<MaybeUninit<T>>().as_mut()
+    }
+
+    /// Reinterpret the `[u8]` stored in this `ErasedBox` as a `[T]` in place, dividing the stored
+    /// length by the size of `T`. Afterwards, the box may be reified as a `[T]` by reference, and
+    /// dropping it will drop the elements as `T`.
+    ///
+    /// The allocation keeps its original layout, so the box must not be converted back with
+    /// [`reify_box`](Self::reify_box) afterwards. Any tracked type information is cleared.
+    ///
+    /// # Safety
+    ///
+    /// The box must store a `[u8]` created from a `Box<[u8]>`. `T` must not be zero-sized, the
+    /// length must be a multiple of the size of `T`, the data must be aligned for `T`, and the
+    /// bytes must be valid values of `T`.
+    pub unsafe fn reinterpret_slice<T>(&mut self) {
+        let len = self.meta.get::<[u8]>();
+        let size = mem::size_of::<T>();
+        debug_assert!(size != 0, "cannot reinterpret a slice as a zero-sized type");
+        debug_assert!(
+            len.is_multiple_of(size),
+            "slice length is not a multiple of the element size"
+        );
+        debug_assert!(
+            self.data.cast::<T>().is_aligned(),
+            "slice data is not aligned for the element type"
+        );
+
+        self.meta = RawMeta::new::<[T]>(len / size);
+        (self.vtable().release)(self.vtable);
+        self.vtable = NonNull::from(&VtableFor::<[T]>::REINTERPRETED);
+        #[cfg(feature = "type_name")]
+        {
+            self.type_name = core::any::type_name::<[T]>();
+        }
     }
 }
 
@@ -131,7 +1182,10 @@ impl fmt::Debug for ErasedBox {
     }
 }
 
-impl<T: ?Sized> From<Box<T>> for ErasedBox {
+/// Erase any `Box`, without tracking the type of its value. This includes a `Box<dyn Any>`, which
+/// is stored as a `dyn Any` that can't be downcast. Use [`ErasedBox::from_any`] to keep the type of
+/// its value instead.
+impl<T: ?Sized + Pointee<Metadata: ErasableMeta>> From<Box<T>> for ErasedBox {
     fn from(b: Box<T>) -> Self {
         let val = NonNull::from(Box::leak(b));
         // SAFETY: We just got this pointer from `Box::leak`, it's sure to uphold the requirements
@@ -141,31 +1195,304 @@ impl<T: ?Sized> From<Box<T>> for ErasedBox {
 
 impl Drop for ErasedBox {
     fn drop(&mut self) {
-        (self.drop)(self.data, self.meta)
+        // SAFETY: These are the parts of this box, which is being dropped
+        unsafe { RawDrop(self.vtable).call(self.data, self.meta) }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloc::string::String;
     use alloc::format;
+    use alloc::rc::Rc;
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::net::Ipv4Addr;
+    use core::ops::Range;
+    use core::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+    use core::time::Duration;
 
     #[test]
     fn test_eb_drop() {
         ErasedBox::new::<i32>(1);
     }
 
+    #[test]
+    fn test_eb_try_new() {
+        let eb = ErasedBox::try_new::<u64>(5).unwrap();
+        assert_eq!(*unsafe { eb.reify_ref::<u64>() }, 5);
+    }
+
+    #[test]
+    fn test_eb_downcast() {
+        let mut eb = ErasedBox::new_any::<u32>(1);
+        assert_eq!(eb.type_id(), Some(TypeId::of::<u32>()));
+        assert_eq!(eb.downcast_ref::<u32>(), Some(&1));
+        assert_eq!(eb.downcast_ref::<i32>(), None);
+        assert_eq!(eb.downcast_mut::<u64>(), None);
+
+        *eb.downcast_mut::<u32>().unwrap() = 2;
+        assert_eq!(eb.downcast_ref::<u32>(), Some(&2));
+
+        let eb = ErasedBox::new::<u32>(1);
+        assert_eq!(eb.type_id(), None);
+        assert_eq!(eb.downcast_ref::<u32>(), None);
+    }
+
+    #[test]
+    fn test_reify_array_ref() {
+        let eb = ErasedBox::new([1u8; 16]);
+        assert_eq!(unsafe { eb.reify_array_ref::<u8, 16>() }, &[1; 16]);
+
+        let eb = ErasedBox::new_array([1u16, 2, 3]);
+        assert_eq!(unsafe { eb.reify_array_ref::<u16, 3>() }, &[1, 2, 3]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "stored value is not the size of the requested array"]
+    fn test_reify_array_ref_wrong_len() {
+        let eb = ErasedBox::new([1u8; 16]);
+        unsafe { eb.reify_array_ref::<u8, 8>() };
+    }
+
+    #[test]
+    fn test_from_parts() {
+        fn free_block(data: NonNull<()>, meta: RawMeta) {
+            let len = unsafe { meta.get::<[u32]>() };
+            let layout = Layout::array::<u32>(len).unwrap();
+            unsafe { alloc::alloc::dealloc(data.as_ptr().cast(), layout) };
+        }
+
+        let layout = Layout::array::<u32>(4).unwrap();
+        let data = NonNull::new(unsafe { alloc::alloc::alloc(layout) }).unwrap();
+        unsafe { ptr::copy_nonoverlapping([1u32, 2, 3, 4].as_ptr(), data.as_ptr().cast(), 4) };
+
+        let eb = unsafe { ErasedBox::from_parts::<[u32]>(data.cast(), 4, free_block) };
+        assert_eq!(unsafe { eb.reify_ref::<[u32]>() }, [1, 2, 3, 4]);
+        drop(eb);
+    }
+
+    #[test]
+    fn test_from_parts_reify_value() {
+        static FREED: AtomicBool = AtomicBool::new(false);
+
+        fn free_string(data: NonNull<()>, _: RawMeta) {
+            unsafe { alloc::alloc::dealloc(data.as_ptr().cast(), Layout::new::<String>()) };
+            FREED.store(true, AtomicOrdering::Relaxed);
+        }
+
+        let data = NonNull::new(unsafe { alloc::alloc::alloc(Layout::new::<String>()) }).unwrap();
+        unsafe { data.cast::<String>().write(String::from("foo")) };
+
+        let eb = unsafe { ErasedBox::from_parts::<String>(data.cast(), (), free_string) };
+        let val = unsafe { eb.reify_value::<String>() };
+        assert_eq!(val, "foo");
+        assert!(FREED.load(AtomicOrdering::Relaxed));
+    }
+
+    #[test]
+    fn test_try_reify_ref() {
+        let eb = ErasedBox::new(String::from("foo"));
+        assert_eq!(unsafe { eb.try_reify_ref::<String>() }.unwrap(), "foo");
+
+        let eb = ErasedBox::from(Box::<[u16]>::from(&[1, 2, 3][..]));
+        assert_eq!(unsafe { eb.try_reify_ref::<[u16]>() }.unwrap(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_try_reify_ref_size_mismatch() {
+        let eb = ErasedBox::new(1u32);
+        assert!(unsafe { eb.try_reify_ref::<u64>() }.is_none());
+        assert!(unsafe { eb.try_reify_ref::<[u8; 2]>() }.is_none());
+
+        let eb = ErasedBox::from(Box::<[u16]>::from(&[1, 2, 3][..]));
+        assert!(unsafe { eb.try_reify_ref::<[u32]>() }.is_none());
+        assert!(unsafe { eb.try_reify_ref::<str>() }.is_none());
+    }
+
+    #[test]
+    fn test_with_ref() {
+        let mut eb = ErasedBox::new(String::from("foo"));
+        assert_eq!(unsafe { eb.with_ref(String::len) }, 3);
+        unsafe { eb.with_mut(|s: &mut String| s.push_str("bar")) };
+        assert_eq!(
+            unsafe { eb.with_ref(|s: &String| s.to_uppercase()) },
+            "FOOBAR"
+        );
+    }
+
+    #[test]
+    fn test_as_bytes() {
+        let eb = ErasedBox::new(0x0102_0304u32);
+        let expected = if cfg!(target_endian = "little") {
+            [4, 3, 2, 1]
+        } else {
+            [1, 2, 3, 4]
+        };
+        assert_eq!(unsafe { eb.as_bytes() }, expected);
+
+        let eb = ErasedBox::from(Box::<[u16]>::from(&[1, 2][..]));
+        assert_eq!(unsafe { eb.as_bytes() }.len(), 4);
+    }
+
+    #[test]
+    fn test_reify_slice() {
+        let eb = ErasedBox::from(Box::<[u32]>::from(&[1, 2, 3][..]));
+        assert_eq!(unsafe { eb.reify_slice::<u32>() }, [1, 2, 3]);
+
+        let eb = ErasedBox::from_vec(vec![(); 4]);
+        assert_eq!(unsafe { eb.reify_slice::<()>() }.len(), 4);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "stored value is not the size of the requested slice"]
+    fn test_reify_slice_wrong_type() {
+        let eb = ErasedBox::from(Box::<[u32]>::from(&[1, 2, 3][..]));
+        unsafe { eb.reify_slice::<u8>() };
+    }
+
+    #[test]
+    fn test_reify_ref_with() {
+        let eb = ErasedBox::new([1u8, 2, 3, 4]);
+        assert_eq!(unsafe { eb.reify_ref_with::<[u8]>(4) }, [1, 2, 3, 4]);
+        assert_eq!(unsafe { eb.reify_ref_with::<[u8]>(2) }, [1, 2]);
+    }
+
+    #[test]
+    fn test_as_raw_parts() {
+        let rc = Rc::new(());
+        let eb = ErasedBox::from_vec(vec![rc.clone(), rc.clone()]);
+        let (data, meta, drop) = eb.as_raw_parts();
+        assert_eq!(data, eb.raw_ptr());
+        assert_eq!(unsafe { meta.get::<[Rc<()>]>() }, 2);
+
+        mem::forget(eb);
+        unsafe { drop.call(data, meta) };
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn test_is_aligned_for() {
+        let eb = ErasedBox::new(0u64);
+        assert!(eb.is_aligned_for::<u64>());
+        assert!(eb.is_aligned_for::<u8>());
+    }
+
+    #[test]
+    fn test_reify_ref_with_type() {
+        let eb = ErasedBox::new_any(String::from("foo"));
+        let (val, type_id) = eb.reify_ref_with_type::<String>().unwrap();
+        assert_eq!(val, "foo");
+        assert_eq!(type_id, TypeId::of::<String>());
+
+        assert!(eb.reify_ref_with_type::<&str>().is_none());
+        assert!(ErasedBox::new(1u8).reify_ref_with_type::<u8>().is_none());
+    }
+
+    #[test]
+    fn test_eb_cmp() {
+        let mut boxes: Vec<ErasedBox> = [5u32, 1, 3].into_iter().map(ErasedBox::new_ord).collect();
+        boxes.sort_by(|a, b| a.type_erased_cmp(b).unwrap());
+        let vals: Vec<u32> = boxes
+            .iter()
+            .map(|eb| *eb.downcast_ref::<u32>().unwrap())
+            .collect();
+        assert_eq!(vals, [1, 3, 5]);
+
+        let other = ErasedBox::new_ord::<i32>(1);
+        assert_eq!(boxes[0].type_erased_cmp(&other), None);
+        let untracked = ErasedBox::new::<u32>(1);
+        assert_eq!(boxes[0].type_erased_cmp(&untracked), None);
+        assert_eq!(
+            ErasedBox::new_any(2u32).type_erased_cmp(&boxes[0]),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            boxes[0].type_erased_cmp(&ErasedBox::new_any(2u32)),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            ErasedBox::new_any(1u32).type_erased_cmp(&ErasedBox::new_any(1u32)),
+            None
+        );
+    }
+
     #[test]
     fn test_eb_reify_box() {
         unsafe { ErasedBox::new::<u32>(1).reify_box::<u32>() };
     }
 
+    #[test]
+    fn test_eb_reify_value() {
+        let eb = ErasedBox::new(String::from("foo"));
+        let val = unsafe { eb.reify_value::<String>() };
+        assert_eq!(val, "foo");
+
+        let eb = ErasedBox::new(());
+        unsafe { eb.reify_value::<()>() };
+    }
+
+    #[test]
+    fn test_eb_reify_manually() {
+        let eb = ErasedBox::new(String::from("foo"));
+        let mut val = unsafe { eb.reify_manually::<String>() };
+        assert_eq!(*val, "foo");
+        unsafe { ManuallyDrop::drop(&mut val) };
+    }
+
+    #[test]
+    fn test_eb_into_uninit_box() {
+        let eb = ErasedBox::new(String::from("foo"));
+        let ptr = eb.raw_ptr();
+        let b = unsafe { eb.into_uninit_box::<String>() };
+        assert_eq!(NonNull::from(&*b).cast(), ptr);
+
+        let b = Box::write(b, String::from("bar"));
+        assert_eq!(*b, "bar");
+    }
+
+    #[test]
+    fn test_eb_map() {
+        let eb = ErasedBox::new::<u32>(5);
+        let eb = unsafe { eb.map(|val: u32| format!("{}", val)) };
+        assert_eq!(unsafe { eb.reify_ref::<String>() }, "5");
+
+        let eb = ErasedBox::new_any::<u32>(5);
+        let eb = eb.try_map(|val: i32| val + 1).unwrap_err();
+        let eb = eb.try_map(|val: u32| format!("{}", val)).unwrap();
+        assert_eq!(eb.downcast_ref::<String>().unwrap(), "5");
+    }
+
+    #[test]
+    fn test_eb_leak() {
+        let eb = ErasedBox::new(String::from("foo"));
+        let (data, meta, _) = eb.leak();
+        assert_eq!(unsafe { data.cast::<String>().as_ref() }, "foo");
+        unsafe { drop_erased_raw::<String>(data, meta) };
+    }
+
+    #[test]
+    fn test_eb_leak_vec() {
+        let mut vec = Vec::with_capacity(8);
+        vec.extend([String::from("foo"), String::from("bar")]);
+
+        let eb = ErasedBox::from_vec(vec);
+        let (data, meta, drop) = eb.leak();
+        assert_eq!(
+            unsafe { reify_ptr::<[String]>(data, meta).as_ref() },
+            ["foo", "bar"]
+        );
+        unsafe { drop.call(data, meta) };
+    }
+
     #[test]
     fn test_eb_reify_ref() {
         let eb = ErasedBox::new::<bool>(true);
         let val = unsafe { eb.reify_ref::<bool>() };
-        assert_eq!(*val, true);
+        assert!(*val);
     }
 
     #[test]
@@ -178,6 +1505,69 @@ mod tests {
         assert_eq!(*val2, 2.5);
     }
 
+    #[test]
+    fn test_eb_reify_uninit_mut() {
+        let mut eb = ErasedBox::new(String::from("foo"));
+        unsafe { ptr::drop_in_place(eb.reify_mut::<String>()) };
+        unsafe { eb.reify_uninit_mut::<String>() }.write(String::from("bar"));
+        assert_eq!(unsafe { eb.reify_ref::<String>() }, "bar");
+    }
+
+    #[test]
+    fn test_eb_swap() {
+        let mut eb1 = ErasedBox::new::<u8>(1);
+        let mut eb2 = ErasedBox::new(String::from("foo"));
+        let ptr1 = eb1.raw_ptr();
+
+        eb1.swap(&mut eb2);
+        assert_eq!(eb2.raw_ptr(), ptr1);
+        assert_eq!(unsafe { eb1.reify_ref::<String>() }, "foo");
+        assert_eq!(*unsafe { eb2.reify_ref::<u8>() }, 1);
+    }
+
+    #[test]
+    fn test_eb_replace() {
+        let mut eb = ErasedBox::new::<Vec<u8>>(vec![1, 2, 3]);
+        let old = eb.replace(ErasedBox::new::<u32>(4));
+
+        assert_eq!(unsafe { old.reify_ref::<Vec<u8>>() }, &[1, 2, 3]);
+        assert_eq!(*unsafe { eb.reify_ref::<u32>() }, 4);
+    }
+
+    #[test]
+    fn test_eb_swap_values() {
+        let mut eb1 = ErasedBox::new(String::from("foo"));
+        let mut eb2 = ErasedBox::new(String::from("bar"));
+        let ptr1 = eb1.raw_ptr();
+
+        unsafe { eb1.swap_values::<String>(&mut eb2) };
+        assert_eq!(eb1.raw_ptr(), ptr1);
+        assert_eq!(unsafe { eb1.reify_ref::<String>() }, "bar");
+        assert_eq!(unsafe { eb2.reify_ref::<String>() }, "foo");
+    }
+
+    #[test]
+    fn test_std_types() {
+        let dur = Duration::new(5, 123_456_789);
+        let eb = ErasedBox::new(dur);
+        assert_eq!(*unsafe { eb.reify_ref::<Duration>() }, dur);
+        assert_eq!(unsafe { eb.reify_value::<Duration>() }, dur);
+
+        let addr = Ipv4Addr::new(127, 0, 0, 1);
+        let eb = ErasedBox::new(addr);
+        assert_eq!(*unsafe { eb.reify_ref::<Ipv4Addr>() }, addr);
+        assert_eq!(unsafe { eb.reify_value::<Ipv4Addr>() }, addr);
+
+        let eb = ErasedBox::new::<Range<usize>>(3..10);
+        assert_eq!(*unsafe { eb.reify_ref::<Range<usize>>() }, 3..10);
+        assert_eq!(unsafe { eb.reify_value::<Range<usize>>() }, 3..10);
+
+        let tuple: (u8, u16, u32) = (u8::MAX, 0xABCD, 0xDEAD_BEEF);
+        let eb = ErasedBox::new(tuple);
+        assert_eq!(*unsafe { eb.reify_ref::<(u8, u16, u32)>() }, tuple);
+        assert_eq!(unsafe { eb.reify_value::<(u8, u16, u32)>() }, tuple);
+    }
+
     #[test]
     fn test_zst() {
         #[derive(Debug, PartialEq)]
@@ -193,10 +1583,78 @@ mod tests {
         assert_eq!(unsafe { eb.reify_ref::<str>() }, "foo");
     }
 
+    #[test]
+    fn test_array() {
+        let eb = ErasedBox::new_array([1, 2, 3]);
+        assert_eq!(unsafe { eb.reify_ref::<[i32]>() }, [1, 2, 3]);
+        assert_eq!(unsafe { eb.reify_ref::<[i32; 3]>() }, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_slice_with_len() {
+        #[repr(C, align(4))]
+        struct Buffer([u8; 8]);
+
+        let bytes = [1u32.to_ne_bytes(), 2u32.to_ne_bytes()].concat();
+        let eb = ErasedBox::new(Buffer(bytes.try_into().unwrap()));
+        let len = mem::size_of::<Buffer>() / mem::size_of::<u32>();
+        assert_eq!(unsafe { eb.reify_slice_with_len::<u32>(len) }, [1, 2]);
+    }
+
+    #[test]
+    fn test_str_box() {
+        let eb: ErasedBox = String::from("foo").into_boxed_str().into();
+        let b = unsafe { eb.reify_box::<str>() };
+        assert_eq!(&*b, "foo");
+
+        let eb: ErasedBox = String::from("bar").into_boxed_str().into();
+        let b = unsafe { eb.reify_boxed_str() };
+        assert_eq!(&*b, "bar");
+    }
+
+    #[test]
+    fn test_str_checked() {
+        let eb: ErasedBox = (Box::new(*b"foo") as Box<[u8]>).into();
+        assert_eq!(unsafe { eb.reify_str_checked() }, Ok("foo"));
+
+        let eb: ErasedBox = (Box::new([0xC3u8, 0x28]) as Box<[u8]>).into();
+        assert!(unsafe { eb.reify_str_checked() }.is_err());
+    }
+
+    #[test]
+    fn test_fn_mut() {
+        let mut count = 0;
+        let mut eb: ErasedBox = (Box::new(|| count += 1) as Box<dyn FnMut()>).into();
+        unsafe { eb.reify_fn_mut()() };
+        unsafe { eb.reify_fn_mut()() };
+        drop(eb);
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_writer() {
+        let mut eb = ErasedBox::new_writer(String::new());
+        write!(unsafe { eb.reify_writer() }, "hi").unwrap();
+        // The data pointer is that of the original `String`
+        assert_eq!(unsafe { eb.raw_ptr().cast::<String>().as_ref() }, "hi");
+    }
+
+    #[test]
+    fn test_fn_once() {
+        let s = String::from("foo");
+        let mut out = None;
+        let eb: ErasedBox = (Box::new(|| out = Some(s)) as Box<dyn FnOnce() + '_>).into();
+        unsafe { eb.invoke_once() };
+        assert_eq!(out.as_deref(), Some("foo"));
+    }
+
     #[test]
     fn test_dyn_val() {
         let eb: ErasedBox = (Box::new(123.45) as Box<dyn fmt::Debug>).into();
-        assert_eq!(format!("{:?}", unsafe { eb.reify_ref::<dyn fmt::Debug>() }), "123.45");
+        assert_eq!(
+            format!("{:?}", unsafe { eb.reify_ref::<dyn fmt::Debug>() }),
+            "123.45"
+        );
     }
 
     #[test]
@@ -204,4 +1662,215 @@ mod tests {
         let eb: ErasedBox = (Box::new([1, 2, 3]) as Box<[i32]>).into();
         assert_eq!(unsafe { eb.reify_ref::<[i32]>() }, [1, 2, 3]);
     }
+
+    #[test]
+    fn test_spare_capacity() {
+        let mut v = Vec::<i32>::with_capacity(10);
+        v.extend([1, 2, 3]);
+        let eb = ErasedBox::from_vec(v);
+        assert_eq!(unsafe { eb.reify_ref::<[i32]>() }, [1, 2, 3]);
+        assert_eq!(unsafe { eb.spare_capacity::<i32>() }, 7);
+    }
+
+    #[test]
+    fn test_reify_vec() {
+        let mut v = Vec::with_capacity(4);
+        v.push(String::from("a"));
+        let eb = ErasedBox::from_vec(v);
+        let mut v = unsafe { eb.reify_vec::<String>() };
+        assert_eq!(v.capacity(), 4);
+        v.push(String::from("b"));
+        assert_eq!(v, ["a", "b"]);
+
+        drop(ErasedBox::from_vec(v));
+    }
+
+    #[test]
+    fn test_reify_into_slot() {
+        struct Dest {
+            name: Option<String>,
+        }
+
+        let mut dest = Dest { name: None };
+        unsafe { ErasedBox::new(String::from("foo")).reify_into_slot(&mut dest.name) };
+        assert_eq!(dest.name.as_deref(), Some("foo"));
+    }
+
+    #[test]
+    fn test_reify_into_boxed_slice() {
+        let mut v = Vec::with_capacity(8);
+        v.extend([1i32, 2, 3]);
+        let eb = ErasedBox::new(v);
+        let b = unsafe { eb.reify_into_boxed_slice::<i32>() };
+        assert_eq!(*b, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_reinterpret_slice() {
+        // SAFETY: `[u32; 2]` and `[u8; 8]` have the same size, and any bytes are valid `u8`s
+        let bytes = unsafe { mem::transmute::<[u32; 2], [u8; 8]>([1, 2]) };
+
+        // The global allocator aligns an 8 byte allocation for `u32`
+        let mut eb = ErasedBox::new_array(bytes);
+        assert!(eb.raw_ptr().cast::<u32>().is_aligned());
+        unsafe { eb.reinterpret_slice::<u32>() };
+        assert_eq!(unsafe { eb.reify_ref::<[u32]>() }, [1, 2]);
+    }
+
+    #[test]
+    fn test_fmt() {
+        let eb = ErasedBox::new(5u8);
+        assert!(format!("{:?}", eb).contains("ErasedBox"));
+        assert_eq!(format!("{:p}", eb), format!("{:p}", eb.raw_ptr()));
+    }
+
+    #[test]
+    fn test_reify_split_at() {
+        let eb = ErasedBox::new_array([1, 2, 3, 4]);
+        let (left, right) = unsafe { eb.reify_split_at::<i32>(2) };
+        assert_eq!(left, [1, 2]);
+        assert_eq!(right, [3, 4]);
+    }
+
+    #[test]
+    fn test_same_type() {
+        let a = ErasedBox::new_any(1u32);
+        assert_eq!(a.same_type(&ErasedBox::new_any(2u32)), Some(true));
+        assert_eq!(a.same_type(&ErasedBox::new_any(2u64)), Some(false));
+        assert_eq!(
+            ErasedBox::new(1u8).same_type(&ErasedBox::new(String::new())),
+            None
+        );
+    }
+
+    #[test]
+    fn test_from_any() {
+        let eb = ErasedBox::from_any(Box::new(5u32) as Box<dyn Any>);
+        assert_eq!(eb.type_id(), Some(TypeId::of::<u32>()));
+        assert_eq!(eb.downcast_ref::<u32>(), Some(&5));
+        assert_eq!(eb.downcast_ref::<i32>(), None);
+
+        let eb = ErasedBox::from_any(Box::new(String::from("foo")));
+        let eb = eb.try_map(|val: String| val.len()).unwrap();
+        assert_eq!(eb.downcast_ref::<usize>(), Some(&3));
+
+        // `From` treats a `Box<dyn Any>` like any other box, so the type isn't tracked
+        let eb = ErasedBox::from(Box::new(5u32) as Box<dyn Any>);
+        assert_eq!(eb.type_id(), None);
+        assert!(!eb.is::<u32>());
+        assert_eq!(eb.downcast_ref::<u32>(), None);
+        assert_eq!(
+            unsafe { eb.reify_ref::<dyn Any>() }.downcast_ref(),
+            Some(&5u32)
+        );
+    }
+
+    #[test]
+    fn test_into_any() {
+        let eb = ErasedBox::new_any(5u64);
+        let any = eb.into_any().unwrap();
+        assert_eq!(any.downcast::<u64>().ok().as_deref(), Some(&5));
+
+        let eb = ErasedBox::from_any(Box::new(String::from("foo")));
+        let any = eb.into_any().unwrap();
+        assert_eq!(any.downcast_ref::<String>().unwrap(), "foo");
+
+        assert!(ErasedBox::new(5u64).into_any().is_err());
+    }
+
+    #[test]
+    fn test_reify_sized_ref() {
+        let eb = ErasedBox::new(Duration::from_secs(3));
+        assert_eq!(unsafe { eb.reify_sized_ref::<Duration>() }, unsafe {
+            eb.reify_ref::<Duration>()
+        });
+    }
+
+    #[test]
+    fn test_reify_dyn() {
+        let meta = ptr::metadata(&0u32 as &dyn fmt::Debug);
+        let eb = ErasedBox::new(5u32);
+        assert_eq!(format!("{:?}", unsafe { eb.reify_dyn(meta) }), "5");
+    }
+
+    #[test]
+    fn test_type_name() {
+        let eb = ErasedBox::new(Vec::<u8>::new());
+        if cfg!(feature = "type_name") {
+            assert_eq!(eb.type_name(), Some(core::any::type_name::<Vec<u8>>()));
+        } else {
+            assert_eq!(eb.type_name(), None);
+        }
+    }
+
+    #[test]
+    fn test_reify_replace() {
+        let eb = ErasedBox::new(5i32);
+        let (old, eb) = unsafe { eb.reify_replace::<i32, _>(String::from("foo")) };
+        assert_eq!(old, 5);
+        assert_eq!(unsafe { eb.reify_ref::<String>() }, "foo");
+
+        let ptr = eb.raw_ptr();
+        let (old, eb) = unsafe { eb.reify_replace::<String, _>(Vec::<u8>::from([1, 2])) };
+        assert_eq!(old, "foo");
+        assert_eq!(eb.raw_ptr(), ptr);
+        assert_eq!(unsafe { eb.reify_ref::<Vec<u8>>() }, &[1, 2]);
+    }
+
+    #[test]
+    fn test_fat_box_size() {
+        const SIZE: usize = fat_box_size();
+        let mut words = if cfg!(feature = "type_name") { 5 } else { 3 };
+        if cfg!(feature = "checksum") {
+            words += 3;
+        }
+        assert_eq!(SIZE, words * mem::size_of::<usize>());
+    }
+
+    #[test]
+    fn test_cell() {
+        let eb = ErasedBox::new(Cell::new(1i32));
+        let cell = unsafe { eb.reify_ref::<Cell<i32>>() };
+        cell.set(cell.get() + 1);
+        assert_eq!(unsafe { eb.reify_cell_get::<i32>() }, 2);
+    }
+
+    #[cfg(feature = "checksum")]
+    #[test]
+    fn test_verify() {
+        let eb = unsafe { ErasedBox::new_checked([1u32, 2, 3, 4]) };
+        assert!(eb.verify());
+
+        // SAFETY: Corrupt one byte of the payload in place
+        unsafe { *eb.raw_ptr().cast::<u8>().as_ptr().add(5) ^= 0xFF };
+        assert!(!eb.verify());
+
+        assert!(ErasedBox::new(5i32).verify());
+    }
+
+    #[test]
+    fn test_assert_valid() {
+        ErasedBox::new(5u32).assert_valid();
+        ErasedBox::new_any(5u32).assert_valid();
+        ErasedBox::from_vec(vec![1u8, 2, 3]).assert_valid();
+        ErasedBox::from(Box::new(1.5f32) as Box<dyn fmt::Debug>).assert_valid();
+    }
+
+    #[test]
+    #[should_panic = "ErasedBox data pointer is misaligned"]
+    fn test_assert_valid_misaligned() {
+        // Never dropped, as the corrupted pointer can't be freed
+        let mut eb = ManuallyDrop::new(ErasedBox::new(5u32));
+        eb.data = eb.data.map_addr(|addr| addr.checked_add(1).unwrap());
+        eb.assert_valid();
+    }
+
+    #[test]
+    #[should_panic = "ErasedBox metadata is invalid for its kind"]
+    fn test_assert_valid_bad_meta() {
+        // Never dropped, as the panic leaves the corrupted metadata in place
+        let mut eb = ManuallyDrop::new(ErasedBox::new(5u32));
+        eb.meta = RawMeta::new::<[u32]>(3);
+        eb.assert_valid();
+    }
 }
@@ -0,0 +1,177 @@
+//! An erased box with runtime borrow tracking, similar to a [`RefCell`](core::cell::RefCell)
+
+use core::cell::Cell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+
+use crate::ErasedBox;
+
+/// Borrow state of an [`ErasedCell`]. Positive values count shared borrows, `-1` marks a mutable
+/// borrow.
+type BorrowFlag = isize;
+
+const UNUSED: BorrowFlag = 0;
+const WRITING: BorrowFlag = -1;
+
+/// An [`ErasedBox`] which tracks borrows of its value at runtime, so that shared and mutable
+/// references can't be handed out at the same time. Reifying is still unsafe, as the user must
+/// know the stored type, but aliasing violations panic instead of causing UB.
+pub struct ErasedCell {
+    borrow: Cell<BorrowFlag>,
+    inner: ErasedBox,
+}
+
+impl ErasedCell {
+    /// Create a new `ErasedCell` from an existing [`ErasedBox`]
+    pub fn new(inner: ErasedBox) -> ErasedCell {
+        ErasedCell {
+            borrow: Cell::new(UNUSED),
+            inner,
+        }
+    }
+
+    /// Get back the [`ErasedBox`] stored in this `ErasedCell`
+    pub fn into_inner(self) -> ErasedBox {
+        self.inner
+    }
+
+    /// Immutably borrow the value stored in this `ErasedCell`. Any number of shared borrows may
+    /// exist at once.
+    ///
+    /// # Panics
+    ///
+    /// If the value is currently mutably borrowed
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn borrow<T: ?Sized>(&self) -> Ref<'_, T> {
+        let flag = self.borrow.get();
+        if flag == WRITING {
+            panic!("ErasedCell already mutably borrowed");
+        }
+        self.borrow.set(flag + 1);
+
+        Ref {
+            value: self.inner.reify_ptr(),
+            borrow: &self.borrow,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Mutably borrow the value stored in this `ErasedCell`. No other borrows may exist at the
+    /// same time.
+    ///
+    /// # Panics
+    ///
+    /// If the value is currently borrowed
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn borrow_mut<T: ?Sized>(&self) -> RefMut<'_, T> {
+        if self.borrow.get() != UNUSED {
+            panic!("ErasedCell already borrowed");
+        }
+        self.borrow.set(WRITING);
+
+        RefMut {
+            value: self.inner.reify_ptr(),
+            borrow: &self.borrow,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl fmt::Debug for ErasedCell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedCell")
+            .field("borrow", &self.borrow.get())
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl From<ErasedBox> for ErasedCell {
+    fn from(inner: ErasedBox) -> Self {
+        ErasedCell::new(inner)
+    }
+}
+
+/// A shared borrow of the value in an [`ErasedCell`], created by [`ErasedCell::borrow`]
+pub struct Ref<'a, T: ?Sized> {
+    value: NonNull<T>,
+    borrow: &'a Cell<BorrowFlag>,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<T: ?Sized> Deref for Ref<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: The cell's borrow flag guarantees no mutable borrow exists while we do
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<T: ?Sized> Drop for Ref<'_, T> {
+    fn drop(&mut self) {
+        self.borrow.set(self.borrow.get() - 1);
+    }
+}
+
+/// A mutable borrow of the value in an [`ErasedCell`], created by [`ErasedCell::borrow_mut`]
+pub struct RefMut<'a, T: ?Sized> {
+    value: NonNull<T>,
+    borrow: &'a Cell<BorrowFlag>,
+    _phantom: PhantomData<&'a mut T>,
+}
+
+impl<T: ?Sized> Deref for RefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: The cell's borrow flag guarantees no other borrow exists while we do
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for RefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: The cell's borrow flag guarantees no other borrow exists while we do
+        unsafe { self.value.as_mut() }
+    }
+}
+
+impl<T: ?Sized> Drop for RefMut<'_, T> {
+    fn drop(&mut self) {
+        self.borrow.set(UNUSED);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_borrow() {
+        let cell = ErasedCell::new(ErasedBox::new(5i32));
+        let a = unsafe { cell.borrow::<i32>() };
+        let b = unsafe { cell.borrow::<i32>() };
+        assert_eq!(*a + *b, 10);
+        drop((a, b));
+
+        *unsafe { cell.borrow_mut::<i32>() } += 1;
+        assert_eq!(*unsafe { cell.borrow::<i32>() }, 6);
+    }
+
+    #[test]
+    #[should_panic = "ErasedCell already borrowed"]
+    fn test_double_borrow_mut() {
+        let cell = ErasedCell::new(ErasedBox::new(5i32));
+        let _a = unsafe { cell.borrow_mut::<i32>() };
+        let _b = unsafe { cell.borrow_mut::<i32>() };
+    }
+}
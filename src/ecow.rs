@@ -0,0 +1,135 @@
+//! A clone-on-write erased value, similar to [`Cow`](alloc::borrow::Cow)
+
+use core::fmt;
+
+use crate::{ErasedBox, ErasedRef};
+
+/// An erased value which is either borrowed or owned, cloning borrowed values into an owned
+/// [`ErasedBox`] when needed. As the type is erased, cloning requires the user to provide it, so
+/// upgrading to owned is unsafe like reifying.
+pub enum ErasedCow<'a> {
+    /// A borrowed value
+    Borrowed(ErasedRef<'a>),
+    /// An owned value
+    Owned(ErasedBox),
+}
+
+impl<'a> ErasedCow<'a> {
+    /// Check whether this `ErasedCow` borrows its value
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, ErasedCow::Borrowed(_))
+    }
+
+    /// Check whether this `ErasedCow` owns its value
+    pub fn is_owned(&self) -> bool {
+        matches!(self, ErasedCow::Owned(_))
+    }
+
+    /// Get a reference to the value in this `ErasedCow`, whether it's borrowed or owned
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the cow
+    pub unsafe fn reify_ref<T: ?Sized>(&self) -> &T {
+        match self {
+            ErasedCow::Borrowed(r) => r.reify_ref(),
+            ErasedCow::Owned(b) => b.reify_ref(),
+        }
+    }
+
+    /// Clone a borrowed value into a new [`ErasedBox`], making this `ErasedCow` owned. Does
+    /// nothing if it's already owned.
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the cow
+    pub unsafe fn to_owned<T: Clone>(&mut self) {
+        if let ErasedCow::Borrowed(r) = self {
+            *self = ErasedCow::Owned(ErasedBox::new(r.reify_ref::<T>().clone()));
+        }
+    }
+
+    /// Get a mutable reference to the value in this `ErasedCow`, cloning it first if it's
+    /// borrowed
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the cow
+    pub unsafe fn reify_mut<T: Clone>(&mut self) -> &mut T {
+        self.to_owned::<T>();
+        match self {
+            ErasedCow::Owned(b) => b.reify_mut(),
+            ErasedCow::Borrowed(_) => unreachable!(),
+        }
+    }
+
+    /// Get the owned value of this `ErasedCow`, cloning it if it's borrowed
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the cow
+    pub unsafe fn into_owned<T: Clone>(mut self) -> ErasedBox {
+        self.to_owned::<T>();
+        match self {
+            ErasedCow::Owned(b) => b,
+            ErasedCow::Borrowed(_) => unreachable!(),
+        }
+    }
+}
+
+impl<'a> From<ErasedRef<'a>> for ErasedCow<'a> {
+    fn from(r: ErasedRef<'a>) -> Self {
+        ErasedCow::Borrowed(r)
+    }
+}
+
+impl From<ErasedBox> for ErasedCow<'_> {
+    fn from(b: ErasedBox) -> Self {
+        ErasedCow::Owned(b)
+    }
+}
+
+impl fmt::Debug for ErasedCow<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ErasedCow::Borrowed(r) => f.debug_tuple("Borrowed").field(r).finish(),
+            ErasedCow::Owned(b) => f.debug_tuple("Owned").field(b).finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn test_borrowed() {
+        let s = String::from("foo");
+        let cow = ErasedCow::from(ErasedRef::new(&s));
+        assert!(cow.is_borrowed());
+        assert_eq!(unsafe { cow.reify_ref::<String>() }, "foo");
+    }
+
+    #[test]
+    fn test_to_owned() {
+        let s = String::from("foo");
+        let mut cow = ErasedCow::from(ErasedRef::new(&s));
+
+        unsafe { cow.reify_mut::<String>() }.push_str("bar");
+        assert!(cow.is_owned());
+        assert_eq!(unsafe { cow.reify_ref::<String>() }, "foobar");
+        assert_eq!(s, "foo");
+
+        let owned = unsafe { cow.into_owned::<String>() };
+        assert_eq!(unsafe { owned.reify_ref::<String>() }, "foobar");
+    }
+
+    #[test]
+    fn test_owned() {
+        let mut cow = ErasedCow::from(ErasedBox::new(1u32));
+        unsafe { cow.to_owned::<u32>() };
+        *unsafe { cow.reify_mut::<u32>() } += 1;
+        assert_eq!(*unsafe { cow.reify_ref::<u32>() }, 2);
+    }
+}
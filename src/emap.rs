@@ -0,0 +1,111 @@
+//! A map storing at most one value of each type, keyed by [`TypeId`]
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use core::any::{Any, TypeId};
+use core::fmt;
+
+use crate::ErasedBox;
+
+/// A map from types to a single value of that type, similar to the `anymap` crate, but usable
+/// without `std`. Each value is stored in an [`ErasedBox`] tracking its type, so all access is
+/// safe.
+#[derive(Default)]
+pub struct ErasedMap {
+    values: BTreeMap<TypeId, ErasedBox>,
+}
+
+impl ErasedMap {
+    /// Create a new, empty `ErasedMap`
+    pub fn new() -> ErasedMap {
+        ErasedMap {
+            values: BTreeMap::new(),
+        }
+    }
+
+    /// Get the number of values in this `ErasedMap`
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Check whether this `ErasedMap` contains no values
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Check whether this `ErasedMap` contains a value of type `T`
+    pub fn contains<T: Any>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Insert a value into this `ErasedMap`, returning the previous value of the same type if
+    /// there was one
+    pub fn insert<T: Any>(&mut self, val: T) -> Option<Box<T>> {
+        self.values
+            .insert(TypeId::of::<T>(), ErasedBox::new_any(val))
+            .map(downcast_box)
+    }
+
+    /// Get a reference to the value of type `T` in this `ErasedMap`, if there is one
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.values.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    /// Get a mutable reference to the value of type `T` in this `ErasedMap`, if there is one
+    pub fn get_mut<T: Any>(&mut self) -> Option<&mut T> {
+        self.values.get_mut(&TypeId::of::<T>())?.downcast_mut()
+    }
+
+    /// Remove the value of type `T` from this `ErasedMap`, returning it if there was one
+    pub fn remove<T: Any>(&mut self) -> Option<Box<T>> {
+        self.values.remove(&TypeId::of::<T>()).map(downcast_box)
+    }
+}
+
+/// Convert a box stored under the key of `T` back into a `Box<T>`
+fn downcast_box<T: Any>(eb: ErasedBox) -> Box<T> {
+    // SAFETY: Values are only ever inserted under the `TypeId` of their own type
+    unsafe { eb.reify_box() }
+}
+
+impl fmt::Debug for ErasedMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedMap")
+            .field("len", &self.values.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn test_insert_get() {
+        let mut map = ErasedMap::new();
+        assert!(map.insert(5u32).is_none());
+        assert!(map.insert(String::from("foo")).is_none());
+        assert_eq!(map.len(), 2);
+
+        assert_eq!(map.get::<u32>(), Some(&5));
+        assert_eq!(map.get::<String>().unwrap(), "foo");
+        assert_eq!(map.get::<u64>(), None);
+        assert_eq!(map.get::<&str>(), None);
+
+        map.get_mut::<String>().unwrap().push_str("bar");
+        assert_eq!(map.insert(String::new()).as_deref().unwrap(), "foobar");
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut map = ErasedMap::new();
+        map.insert(1u8);
+        map.insert(2i8);
+
+        assert_eq!(map.remove::<u8>(), Some(Box::new(1)));
+        assert_eq!(map.remove::<u8>(), None);
+        assert!(!map.contains::<u8>());
+        assert_eq!(map.get::<i8>(), Some(&2));
+    }
+}
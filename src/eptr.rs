@@ -1,42 +1,63 @@
-//! Erased pointer types, all are 3 pointers wide
+//! Erased pointer types, which are 2 pointers wide, or 1 for [`ThinErasedPtr`]
 
-use alloc::boxed::Box;
 use core::ptr::{NonNull, Pointee};
-use core::{fmt, ptr};
+use core::{fmt, mem, ptr};
 
-fn drop_impl<T: ?Sized + Pointee>(meta: NonNull<()>) {
-    // SAFETY: We know that the meta came from a T of this type
-    unsafe { Box::from_raw(meta.cast::<T::Metadata>().as_ptr()) };
-}
+use crate::meta::{ErasableMeta, RawMeta};
 
 /// An erased pointer, pointing to a (possibly unsized) value of unknown type. Creating one
 /// is safe, but converting it back into any type is unsafe as it requires the user to know the type
 /// stored behind the pointer.
 ///
-/// This type will always be three pointers wide, even for sized types, due to needing to store
-/// an unknown metadata.
+/// This type will always be two pointers wide, even for sized types, due to needing to store
+/// an unknown metadata. The metadata is stored inline, so this type is [`Copy`].
 ///
 /// Note that, like [`NonNull`], this type provides `From<&T>`. This has the same invariants as
 /// [`NonNull`], it is UB to mutate through a pointer derived from a shared reference.
+#[derive(Copy, Clone)]
 pub struct ErasedPtr {
     data: *const (),
-    meta: NonNull<()>,
-    drop: fn(NonNull<()>),
+    meta: RawMeta,
 }
 
 impl ErasedPtr {
     /// Create a new `ErasedPtr` from an existing [`*const T`](*const)
-    pub fn new<T: ?Sized>(val: *const T) -> ErasedPtr {
+    pub fn new<T: ?Sized + Pointee<Metadata: ErasableMeta>>(val: *const T) -> ErasedPtr {
         let (data, meta) = val.to_raw_parts();
-        let meta = NonNull::from(Box::leak(Box::new(meta))).cast();
 
         ErasedPtr {
             data,
-            meta,
-            drop: drop_impl::<T>,
+            meta: RawMeta::new::<T>(meta),
+        }
+    }
+
+    /// Create a new `ErasedPtr` from a pointer to a sized `T`. Unlike [`new`](Self::new), this is
+    /// usable in `const` contexts, such as for building `static` tables of erased pointers.
+    pub const fn from_sized<T>(val: *const T) -> ErasedPtr {
+        ErasedPtr {
+            data: val.cast(),
+            meta: RawMeta::sized(),
         }
     }
 
+    /// Create a new `ErasedPtr` with a dangling, but non-null and correctly aligned, data pointer
+    /// for a sized `T`. This is usable in `const` contexts, and is useful as a sentinel that is
+    /// never dereferenced.
+    pub const fn dangling_sized<T>() -> ErasedPtr {
+        ErasedPtr::from_sized(NonNull::<T>::dangling().as_ptr())
+    }
+
+    /// Create a new `ErasedPtr` with a null data pointer, but valid metadata for a `T`. This is
+    /// useful for sentinel values.
+    pub fn null<T: ?Sized + Pointee<Metadata: ErasableMeta>>(meta: T::Metadata) -> ErasedPtr {
+        ErasedPtr::new(ptr::from_raw_parts::<T>(ptr::null::<()>(), meta))
+    }
+
+    /// Check whether the data pointer of this `ErasedPtr` is null
+    pub fn is_null(&self) -> bool {
+        self.data.is_null()
+    }
+
     /// Get the raw pointer to the contained data
     pub fn raw_ptr(&self) -> *const () {
         self.data
@@ -44,12 +65,89 @@ impl ErasedPtr {
 
     /// Get the raw pointer to the contained data mutably
     pub fn raw_ptr_mut(&self) -> *mut () {
-        self.data as *mut ()
+        self.data.cast_mut()
     }
 
-    /// Get the raw pointer to the meta of the contained data
+    /// Get the raw pointer to the meta of the contained data. The metadata is stored inline, so
+    /// this points into the pointer itself.
     pub fn raw_meta_ptr(&self) -> NonNull<()> {
-        self.meta
+        self.meta.as_ptr()
+    }
+
+    /// Check whether the data pointer of this `ErasedPtr` is aligned for a `T`. This is a cheap check
+    /// to make before reifying.
+    pub fn is_aligned_for<T>(&self) -> bool {
+        self.data.cast::<T>().is_aligned()
+    }
+
+    /// Compare the metadata of this `ErasedPtr` with another, without reifying either. This
+    /// allows, for example, checking whether two erased slice pointers have the same length.
+    ///
+    /// As the type of the metadata is erased, this is a best-effort comparison of the raw bytes
+    /// of the inline metadata storage. Metadata of two different types may compare equal, such
+    /// as that of a sized type and of an empty slice. The result should only be trusted when
+    /// both pointers are known to store the same type.
+    pub fn meta_eq(&self, other: &ErasedPtr) -> bool {
+        self.meta == other.meta
+    }
+
+    /// Advance the data pointer of this `ErasedPtr` by a number of bytes, keeping the metadata
+    /// unchanged. This is the erased equivalent of [`<*const T>::wrapping_byte_add`].
+    ///
+    /// The offset uses wrapping arithmetic, so this is always safe to call, and the result keeps
+    /// the provenance of the original pointer. It is only valid to dereference the result if it
+    /// is still in bounds of the original allocation, and points to a value matching the stored
+    /// metadata.
+    ///
+    /// [`<*const T>::wrapping_byte_add`]: https://doc.rust-lang.org/core/primitive.pointer.html#method.wrapping_byte_add
+    pub fn byte_add(mut self, bytes: usize) -> ErasedPtr {
+        self.data = self.data.wrapping_byte_add(bytes);
+        self
+    }
+
+    /// Offset the data pointer of this `ErasedPtr` by a signed number of bytes, keeping the
+    /// metadata unchanged. This is the erased equivalent of [`<*const T>::wrapping_byte_offset`].
+    ///
+    /// The same requirements as [`byte_add`](Self::byte_add) apply to dereferencing the result.
+    ///
+    /// [`<*const T>::wrapping_byte_offset`]: https://doc.rust-lang.org/core/primitive.pointer.html#method.wrapping_byte_offset
+    pub fn byte_offset(mut self, bytes: isize) -> ErasedPtr {
+        self.data = self.data.wrapping_byte_offset(bytes);
+        self
+    }
+
+    /// Advance this `ErasedPtr`, which stores a `[T]`, by `n` elements, shrinking the stored
+    /// length to match so the result points to the remainder of the slice.
+    ///
+    /// # Safety
+    ///
+    /// The pointer must have originally stored a `[T]` of the provided `T`, and `n` must be no
+    /// greater than the stored length. The data pointer must be valid for the whole slice, so the
+    /// offset stays in bounds of the same allocation.
+    pub unsafe fn element_add<T>(mut self, n: usize) -> ErasedPtr {
+        let len = self.meta.get::<[T]>();
+        debug_assert!(n <= len, "element offset out of bounds of the stored slice");
+        self.meta = RawMeta::new::<[T]>(len - n);
+        self.data = self.data.cast::<T>().add(n).cast();
+        self
+    }
+
+    /// Change the type of the value this `ErasedPtr` points to from `T` to `U`, converting the
+    /// metadata with the provided function. This allows casts such as `[u8]` to `[i8]`, keeping
+    /// the length, while preserving the provenance of the data pointer.
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the pointer. The caller is
+    /// responsible for ensuring the layout of `U` with the new metadata is compatible with the
+    /// pointed-to data before it is dereferenced.
+    pub unsafe fn cast_meta<T, U>(self, f: impl FnOnce(T::Metadata) -> U::Metadata) -> ErasedPtr
+    where
+        T: ?Sized + Pointee,
+        U: ?Sized + Pointee<Metadata: ErasableMeta>,
+    {
+        let (data, meta) = self.reify_ptr::<T>().to_raw_parts();
+        ErasedPtr::new(ptr::from_raw_parts::<U>(data, f(meta)))
     }
 
     /// Get a pointer to the value stored in this `ErasedPtr`
@@ -58,8 +156,24 @@ impl ErasedPtr {
     ///
     /// The provided `T` must be the same type as originally stored in the pointer
     pub unsafe fn reify_ptr<T: ?Sized + Pointee>(&self) -> *const T {
-        let meta = self.meta.cast::<T::Metadata>().as_ref();
-        ptr::from_raw_parts(self.data, *meta)
+        ptr::from_raw_parts(self.data, self.meta.get::<T>())
+    }
+
+    /// Get a pointer to the value stored in this `ErasedPtr` using the provided metadata, ignoring
+    /// the stored metadata entirely
+    pub fn reify_ptr_with<T: ?Sized + Pointee>(&self, meta: T::Metadata) -> *const T {
+        ptr::from_raw_parts(self.data, meta)
+    }
+
+    /// Get a reference to the value stored in this `ErasedPtr` using the provided metadata,
+    /// ignoring the stored metadata entirely
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be valid to dereference as a `T` with the provided metadata, and the value
+    /// must not be mutated for the lifetime of the returned reference
+    pub unsafe fn reify_ref_with<T: ?Sized + Pointee>(&self, meta: T::Metadata) -> &T {
+        &*self.reify_ptr_with(meta)
     }
 
     /// Get a mutable pointer to the value stored in this `ErasedPtr`
@@ -68,8 +182,45 @@ impl ErasedPtr {
     ///
     /// The provided `T` must be the same type as originally stored in the pointer
     pub unsafe fn reify_ptr_mut<T: ?Sized + Pointee>(&self) -> *mut T {
-        let meta = self.meta.cast::<T::Metadata>().as_ref();
-        ptr::from_raw_parts_mut(self.data as *mut (), *meta)
+        ptr::from_raw_parts_mut(self.data.cast_mut(), self.meta.get::<T>())
+    }
+
+    /// Get a reference to the value stored in this `ErasedPtr`, borrowed for as long as this
+    /// pointer is borrowed
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the pointer. The pointer must
+    /// be valid to dereference, and the value must not be mutated for the lifetime of the
+    /// returned reference.
+    pub unsafe fn reify_ref<T: ?Sized + Pointee>(&self) -> &T {
+        &*self.reify_ptr()
+    }
+
+    /// Get a reference to the value stored in this `ErasedPtr`, or `None` if the data pointer is
+    /// null
+    ///
+    /// # Safety
+    ///
+    /// The same requirements as [`reify_ref`](Self::reify_ref) apply if the pointer isn't null
+    pub unsafe fn reify_ref_nonnull<T: ?Sized + Pointee>(&self) -> Option<&T> {
+        if self.data.is_null() {
+            None
+        } else {
+            Some(self.reify_ref())
+        }
+    }
+
+    /// Get a mutable reference to the value stored in this `ErasedPtr`, borrowed for as long as
+    /// this pointer is mutably borrowed
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the pointer. The pointer must
+    /// be valid to dereference and write through, and the value must not be accessed through any
+    /// other pointer for the lifetime of the returned reference.
+    pub unsafe fn reify_mut<T: ?Sized + Pointee>(&mut self) -> &mut T {
+        &mut *self.reify_ptr_mut()
     }
 }
 
@@ -88,33 +239,35 @@ impl fmt::Debug for ErasedPtr {
     }
 }
 
-impl<T: ?Sized> From<*const T> for ErasedPtr {
+impl<T: ?Sized + Pointee<Metadata: ErasableMeta>> From<*const T> for ErasedPtr {
     fn from(val: *const T) -> Self {
         ErasedPtr::new(val)
     }
 }
 
-impl<T: ?Sized> From<*mut T> for ErasedPtr {
+impl<T: ?Sized + Pointee<Metadata: ErasableMeta>> From<*mut T> for ErasedPtr {
     fn from(val: *mut T) -> Self {
         ErasedPtr::new(val)
     }
 }
 
-impl<T: ?Sized> From<&T> for ErasedPtr {
+impl<T: ?Sized + Pointee<Metadata: ErasableMeta>> From<&T> for ErasedPtr {
     fn from(val: &T) -> Self {
         ErasedPtr::new(val)
     }
 }
 
-impl<T: ?Sized> From<&mut T> for ErasedPtr {
+impl<T: ?Sized + Pointee<Metadata: ErasableMeta>> From<&mut T> for ErasedPtr {
     fn from(val: &mut T) -> Self {
         ErasedPtr::new(val)
     }
 }
 
-impl Drop for ErasedPtr {
-    fn drop(&mut self) {
-        (self.drop)(self.meta)
+/// The default `ErasedPtr` is [`null`](ErasedPtr::null), storing the metadata of `()`. It must be
+/// replaced with a real pointer before being reified.
+impl Default for ErasedPtr {
+    fn default() -> Self {
+        ErasedPtr::null::<()>(())
     }
 }
 
@@ -122,38 +275,74 @@ impl Drop for ErasedPtr {
 /// is safe, but converting it back into any type is unsafe as it requires the user to know the type
 /// stored behind the pointer.
 ///
-/// This type will always be three pointers wide, even for sized types, due to needing to store
-/// an unknown metadata.
+/// This type will always be two pointers wide, even for sized types, due to needing to store
+/// an unknown metadata. The metadata is stored inline, so this type is [`Copy`].
 ///
 /// Note that, like [`NonNull`], this type provides `From<&T>`. This has the same invariants as
 /// [`NonNull`], it is UB to mutate through a pointer derived from a shared reference.
+///
+/// Equality, ordering and hashing are by identity, comparing the data address and the raw bytes
+/// of the metadata, so two pointers to the same value compare equal. Pointers are ordered by the
+/// address of their data, then by their metadata. This follows where values happen to be placed
+/// in memory, not their contents.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ErasedNonNull {
     data: NonNull<()>,
-    meta: NonNull<()>,
-    drop: fn(NonNull<()>),
+    meta: RawMeta,
 }
 
 impl ErasedNonNull {
     /// Create a new `ErasedPtr` from a [`NonNull<T>`](NonNull)
-    pub fn new<T: ?Sized>(val: NonNull<T>) -> ErasedNonNull {
+    pub fn new<T: ?Sized + Pointee<Metadata: ErasableMeta>>(val: NonNull<T>) -> ErasedNonNull {
         let (data, meta) = val.to_raw_parts();
-        let meta = NonNull::from(Box::leak(Box::new(meta))).cast();
 
         ErasedNonNull {
             data,
-            meta,
-            drop: drop_impl::<T>,
+            meta: RawMeta::new::<T>(meta),
         }
     }
 
+    /// Create a new `ErasedNonNull` with a dangling, but non-null and correctly aligned, data
+    /// pointer for a `T` with the provided metadata. This is useful as a placeholder that is never
+    /// dereferenced.
+    pub fn dangling<T: ?Sized + Pointee<Metadata: ErasableMeta>>(
+        meta: T::Metadata,
+    ) -> ErasedNonNull {
+        // SAFETY: Computing the alignment only requires valid metadata, not a valid data pointer
+        let align =
+            unsafe { mem::align_of_val_raw(ptr::from_raw_parts::<T>(ptr::null::<()>(), meta)) };
+        // SAFETY: Alignment is always non-zero
+        let data = unsafe { NonNull::new_unchecked(ptr::without_provenance_mut::<()>(align)) };
+        ErasedNonNull::new(NonNull::<T>::from_raw_parts(data, meta))
+    }
+
     /// Get the raw pointer to the contained data
     pub fn raw_ptr(&self) -> NonNull<()> {
         self.data
     }
 
-    /// Get the raw pointer to the meta of the contained data
+    /// Get the raw pointer to the meta of the contained data. The metadata is stored inline, so
+    /// this points into the pointer itself.
     pub fn raw_meta_ptr(&self) -> NonNull<()> {
-        self.meta
+        self.meta.as_ptr()
+    }
+
+    /// Check whether the data pointer of this `ErasedNonNull` is aligned for a `T`. This is a cheap check
+    /// to make before reifying.
+    pub fn is_aligned_for<T>(&self) -> bool {
+        self.data.cast::<T>().is_aligned()
+    }
+
+    /// Check whether two `ErasedNonNull` point to the same value, with the same metadata. This is
+    /// the same as comparing them with `==`.
+    pub fn ptr_eq(a: ErasedNonNull, b: ErasedNonNull) -> bool {
+        a == b
+    }
+
+    /// Split this `ErasedNonNull` into two pointers to the same data. As the metadata is stored
+    /// inline, this is the same as copying the pointer.
+    pub fn split(self) -> (ErasedNonNull, ErasedNonNull) {
+        (self, self)
     }
 
     /// Get back the pointer stored in this `ErasedNonNull`
@@ -162,8 +351,18 @@ impl ErasedNonNull {
     ///
     /// The provided `T` must be the same type as originally stored in the pointer
     pub unsafe fn reify_ptr<T: ?Sized + Pointee>(&self) -> NonNull<T> {
-        let meta = self.meta.cast::<T::Metadata>().as_ref();
-        NonNull::from_raw_parts(self.data, *meta)
+        NonNull::from_raw_parts(self.data, self.meta.get::<T>())
+    }
+
+    /// Get a reference to the value this `ErasedNonNull` points to using the provided metadata,
+    /// ignoring the stored metadata entirely
+    ///
+    /// # Safety
+    ///
+    /// The pointer must be valid to dereference as a `T` with the provided metadata, and the value
+    /// must not be mutated for the lifetime of the returned reference
+    pub unsafe fn reify_ref_with<T: ?Sized + Pointee>(&self, meta: T::Metadata) -> &T {
+        NonNull::<T>::from_raw_parts(self.data, meta).as_ref()
     }
 }
 
@@ -182,33 +381,87 @@ impl fmt::Debug for ErasedNonNull {
     }
 }
 
-impl<T: ?Sized> From<NonNull<T>> for ErasedNonNull {
+impl<T: ?Sized + Pointee<Metadata: ErasableMeta>> From<NonNull<T>> for ErasedNonNull {
     fn from(val: NonNull<T>) -> Self {
         ErasedNonNull::new(val)
     }
 }
 
-impl<T: ?Sized> From<&T> for ErasedNonNull {
+impl<T: ?Sized + Pointee<Metadata: ErasableMeta>> From<&T> for ErasedNonNull {
     fn from(val: &T) -> Self {
         ErasedNonNull::new(NonNull::from(val))
     }
 }
 
-impl<T: ?Sized> From<&mut T> for ErasedNonNull {
+impl<T: ?Sized + Pointee<Metadata: ErasableMeta>> From<&mut T> for ErasedNonNull {
     fn from(val: &mut T) -> Self {
         ErasedNonNull::new(NonNull::from(val))
     }
 }
 
-impl Drop for ErasedNonNull {
-    fn drop(&mut self) {
-        (self.drop)(self.meta)
+/// An erased pointer to a sized value of unknown type. As sized types have no metadata, this is
+/// just a data pointer, and is only one pointer wide. Creating one is safe, but converting it back
+/// into any type is unsafe as it requires the user to know the type stored behind the pointer.
+#[derive(Copy, Clone)]
+pub struct ThinErasedPtr {
+    data: *const (),
+}
+
+impl ThinErasedPtr {
+    /// Create a new `ThinErasedPtr` from an existing [`*const T`](*const)
+    pub fn new<T>(val: *const T) -> ThinErasedPtr {
+        ThinErasedPtr { data: val.cast() }
+    }
+
+    /// Get the raw pointer to the contained data
+    pub fn raw_ptr(&self) -> *const () {
+        self.data
+    }
+
+    /// Get a pointer to the value stored in this `ThinErasedPtr`
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the pointer
+    pub unsafe fn reify_ptr<T>(&self) -> *const T {
+        self.data.cast()
+    }
+}
+
+impl fmt::Pointer for ThinErasedPtr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.data, f)
+    }
+}
+
+impl fmt::Debug for ThinErasedPtr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThinErasedPtr")
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+impl<T> From<*const T> for ThinErasedPtr {
+    fn from(val: *const T) -> Self {
+        ThinErasedPtr::new(val)
+    }
+}
+
+impl<T> From<&T> for ThinErasedPtr {
+    fn from(val: &T) -> Self {
+        ThinErasedPtr::new(val)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    extern crate std;
+
     use super::*;
+    use alloc::collections::BTreeSet;
+    use alloc::vec::Vec;
+    use std::collections::HashSet;
 
     #[test]
     fn test_eptr_ptr() {
@@ -231,6 +484,166 @@ mod tests {
         assert_eq!(unsafe { *ptr }, -10);
     }
 
+    #[test]
+    fn test_eptr_ref() {
+        let item: [u8; 3] = [1, 2, 3];
+
+        let ep = ErasedPtr::from(&item as &[u8]);
+        assert_eq!(unsafe { ep.reify_ref::<[u8]>() }, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_eptr_null() {
+        let ep = ErasedPtr::null::<[u32]>(3);
+        assert!(ep.is_null());
+        let ptr = unsafe { ep.reify_ptr::<[u32]>() };
+        assert!(ptr.is_null());
+        assert_eq!(ptr.len(), 3);
+
+        let item: u8 = 1;
+        assert!(!ErasedPtr::from(&item).is_null());
+    }
+
+    #[test]
+    fn test_eptr_const() {
+        // `ErasedPtr` is not `Sync`, like raw pointers, so a static needs a wrapper
+        struct Table([ErasedPtr; 3]);
+        unsafe impl Sync for Table {}
+
+        static VALUE: u32 = 7;
+        static NAME: &str = "foo";
+        static TABLE: Table = Table([
+            ErasedPtr::from_sized(&VALUE),
+            ErasedPtr::from_sized(&NAME),
+            ErasedPtr::dangling_sized::<u64>(),
+        ]);
+
+        assert_eq!(unsafe { *TABLE.0[0].reify_ref::<u32>() }, 7);
+        assert_eq!(unsafe { *TABLE.0[1].reify_ref::<&str>() }, "foo");
+        assert!(!TABLE.0[2].is_null());
+        assert!(TABLE.0[2].is_aligned_for::<u64>());
+        assert!(TABLE.0[0].meta_eq(&ErasedPtr::from(&VALUE)));
+    }
+
+    #[test]
+    fn test_eptr_default() {
+        #[derive(Default)]
+        struct Holder {
+            ptr: ErasedPtr,
+        }
+
+        assert!(ErasedPtr::default().is_null());
+        assert!(Holder::default().ptr.is_null());
+    }
+
+    #[test]
+    fn test_eptr_ref_nonnull() {
+        let ep = ErasedPtr::null::<u8>(());
+        assert_eq!(unsafe { ep.reify_ref_nonnull::<u8>() }, None);
+
+        let item: u8 = 5;
+        let ep = ErasedPtr::from(&item);
+        assert_eq!(unsafe { ep.reify_ref_nonnull::<u8>() }, Some(&5));
+    }
+
+    #[test]
+    fn test_eptr_mut() {
+        let mut item: i16 = -5;
+
+        let mut ep = ErasedPtr::from(&mut item);
+        let val = unsafe { ep.reify_mut::<i16>() };
+        assert_eq!(*val, -5);
+        *val = 10;
+        assert_eq!(unsafe { *ep.reify_ref::<i16>() }, 10);
+    }
+
+    #[test]
+    fn test_eptr_copy() {
+        let item: [u8; 3] = [1, 2, 3];
+
+        let ep = ErasedPtr::from(&item as &[u8]);
+        let ep2 = ep;
+        assert_eq!(unsafe { ep.reify_ref::<[u8]>() }, [1, 2, 3]);
+        assert_eq!(unsafe { ep2.reify_ref::<[u8]>() }, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_size() {
+        assert_eq!(mem::size_of::<ErasedPtr>(), 2 * mem::size_of::<usize>());
+        assert_eq!(mem::size_of::<ErasedNonNull>(), 2 * mem::size_of::<usize>());
+        assert_eq!(mem::size_of::<ThinErasedPtr>(), mem::size_of::<usize>());
+    }
+
+    #[test]
+    fn test_eptr_byte_add() {
+        let item: [u32; 4] = [1, 2, 3, 4];
+
+        let mut ep = ErasedPtr::new(item.as_ptr());
+        for expected in item {
+            assert_eq!(unsafe { *ep.reify_ref::<u32>() }, expected);
+            ep = ep.byte_add(mem::size_of::<u32>());
+        }
+
+        let ep = ep.byte_offset(-2 * mem::size_of::<u32>() as isize);
+        assert_eq!(unsafe { *ep.reify_ref::<u32>() }, 3);
+    }
+
+    #[test]
+    fn test_eptr_element_add() {
+        let item: [u32; 4] = [1, 2, 3, 4];
+
+        let mut ep = ErasedPtr::from(&item as &[u32]);
+        for idx in 0..4 {
+            assert_eq!(unsafe { ep.reify_ref::<[u32]>() }, &item[idx..]);
+            ep = unsafe { ep.element_add::<u32>(1) };
+        }
+        assert_eq!(unsafe { ep.reify_ref::<[u32]>() }, []);
+    }
+
+    #[test]
+    fn test_eptr_meta_eq() {
+        let a: [u8; 3] = [1, 2, 3];
+        let b: [u8; 3] = [4, 5, 6];
+        let c: [u8; 2] = [1, 2];
+
+        let ep_a = ErasedPtr::from(&a as &[u8]);
+        let ep_b = ErasedPtr::from(&b as &[u8]);
+        let ep_c = ErasedPtr::from(&c as &[u8]);
+        assert!(ep_a.meta_eq(&ep_b));
+        assert!(!ep_a.meta_eq(&ep_c));
+        assert!(!ep_a.meta_eq(&ErasedPtr::from(&a)));
+    }
+
+    #[test]
+    fn test_eptr_cast_meta() {
+        let item: [u8; 3] = [1, 2, 255];
+
+        let ep = ErasedPtr::from(&item as &[u8]);
+        let ep = unsafe { ep.cast_meta::<[u8], [i8]>(|len| len) };
+        assert_eq!(unsafe { ep.reify_ref::<[i8]>() }, [1, 2, -1]);
+    }
+
+    #[test]
+    fn test_nonnull_dangling() {
+        let np = ErasedNonNull::dangling::<u64>(());
+        assert_eq!(np.raw_ptr().as_ptr().addr() % mem::align_of::<u64>(), 0);
+
+        let np = ErasedNonNull::dangling::<[u32]>(0);
+        assert_eq!(np.raw_ptr().as_ptr().addr() % mem::align_of::<u32>(), 0);
+        assert_eq!(unsafe { np.reify_ptr::<[u32]>().as_ref() }, []);
+    }
+
+    #[test]
+    fn test_nonnull_split() {
+        let item: [u8; 3] = [1, 2, 3];
+
+        let np = ErasedNonNull::from(&item as &[u8]);
+        let (np1, np2) = np.split();
+        assert_eq!(np1.raw_ptr(), np2.raw_ptr());
+        assert_eq!(unsafe { np1.reify_ptr::<[u8]>().as_ref() }, [1, 2, 3]);
+        assert_eq!(unsafe { np2.reify_ptr::<[u8]>().as_ref() }, [1, 2, 3]);
+    }
+
     #[test]
     fn test_nonnull_ptr() {
         let item: &str = "FOO";
@@ -239,4 +652,87 @@ mod tests {
         let val = unsafe { *np.reify_ptr::<&'static str>().as_ref() };
         assert_eq!(val, "FOO");
     }
+
+    #[test]
+    fn test_nonnull_unsized() {
+        let item = [1u16, 2, 3, 4];
+
+        let np = ErasedNonNull::from(&item[..3]);
+        assert_eq!(unsafe { np.reify_ptr::<[u16]>().as_ref() }, [1, 2, 3]);
+
+        let np = ErasedNonNull::from("FOO");
+        assert_eq!(unsafe { np.reify_ptr::<str>().as_ref() }, "FOO");
+    }
+
+    #[test]
+    fn test_thin_ptr() {
+        let item = (1u8, 2u64);
+
+        let ep = ThinErasedPtr::from(&item);
+        assert_eq!(ep.raw_ptr(), (&item as *const (u8, u64)).cast());
+        assert_eq!(unsafe { *ep.reify_ptr::<(u8, u64)>() }, (1, 2));
+    }
+
+    #[test]
+    fn test_nonnull_hash() {
+        let a = [1u8, 2, 3];
+        let b = [1u8, 2, 3];
+
+        let mut set = HashSet::new();
+        set.insert(ErasedNonNull::from(&a));
+        set.insert(ErasedNonNull::from(&a));
+        set.insert(ErasedNonNull::from(&b));
+        assert_eq!(set.len(), 2);
+
+        assert!(ErasedNonNull::ptr_eq(
+            ErasedNonNull::from(&a),
+            ErasedNonNull::from(&a)
+        ));
+        // Same address, different metadata
+        assert!(!ErasedNonNull::ptr_eq(
+            ErasedNonNull::from(&a as &[u8]),
+            ErasedNonNull::from(&a[..2])
+        ));
+    }
+
+    #[test]
+    fn test_nonnull_ord() {
+        let items = [1u32, 2, 3, 4];
+
+        let set = [3, 0, 2, 1, 0]
+            .into_iter()
+            .map(|i| ErasedNonNull::from(&items[i]))
+            .collect::<BTreeSet<_>>();
+        let vals = set
+            .iter()
+            .map(|ptr| unsafe { *ptr.reify_ptr::<u32>().as_ref() })
+            .collect::<Vec<_>>();
+        assert_eq!(vals, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_is_aligned_for() {
+        let item = [0u32; 2];
+        let ep = ErasedPtr::new(&item);
+        assert!(ep.is_aligned_for::<u32>());
+
+        let odd = ErasedPtr::new(unsafe { item.as_ptr().cast::<u8>().add(1) });
+        assert!(odd.is_aligned_for::<u8>());
+        assert!(!odd.is_aligned_for::<u32>());
+
+        let np = ErasedNonNull::from(unsafe { &*item.as_ptr().cast::<u8>().add(1) });
+        assert!(!np.is_aligned_for::<u32>());
+    }
+
+    #[test]
+    fn test_reify_ref_with() {
+        let item = [1u8, 2, 3, 4];
+
+        let ep = ErasedPtr::new(&item);
+        assert_eq!(unsafe { ep.reify_ref_with::<[u8]>(4) }, [1, 2, 3, 4]);
+        assert_eq!(unsafe { ep.reify_ref_with::<[u8]>(2) }, [1, 2]);
+
+        let np = ErasedNonNull::from(&item);
+        assert_eq!(unsafe { np.reify_ref_with::<[u8]>(2) }, [1, 2]);
+    }
 }
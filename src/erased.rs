@@ -0,0 +1,187 @@
+//! An erased box generic over the kind of metadata it stores, for when the shape of the erased
+//! type is known ahead of time
+
+use alloc::boxed::Box;
+use core::ptr::{NonNull, Pointee};
+use core::{fmt, mem};
+
+use crate::meta::ErasableMeta;
+
+fn drop_box<T: ?Sized + Pointee>(data: NonNull<()>, meta: T::Metadata) {
+    let ptr = NonNull::<T>::from_raw_parts(data, meta);
+    // SAFETY: Data pointer will have come from `Box::leak` of the correct type
+    drop(unsafe { Box::from_raw(ptr.as_ptr()) });
+}
+
+/// An erased box which stores metadata of a known type `M` inline, rather than erasing it as well.
+/// Only types whose metadata is `M` may be stored, so a box of sized types is `Erased<()>`, of
+/// slices is `Erased<usize>`, and of a trait object is `Erased<DynMetadata<dyn Trait>>`.
+///
+/// This makes the box smaller than an [`ErasedBox`](crate::ErasedBox) when the shape of the
+/// erased type is known: `Erased<()>` is two pointers wide, while `Erased<usize>` is three. The
+/// value is stored in the same allocation as a [`Box`] of it would use, so converting to and from
+/// boxes doesn't copy.
+pub struct Erased<M: ErasableMeta> {
+    data: NonNull<()>,
+    meta: M,
+    drop: fn(NonNull<()>, M),
+}
+
+impl Erased<()> {
+    /// Create a new `Erased` from a sized value
+    pub fn new<T>(val: T) -> Erased<()> {
+        Erased::from_box(Box::new(val))
+    }
+}
+
+impl<M: ErasableMeta> Erased<M> {
+    /// Create a new `Erased` from an existing `Box`
+    pub fn from_box<T: ?Sized + Pointee<Metadata = M>>(val: Box<T>) -> Erased<M> {
+        let (data, meta) = NonNull::from(Box::leak(val)).to_raw_parts();
+
+        Erased {
+            data,
+            meta,
+            drop: drop_box::<T>,
+        }
+    }
+
+    /// Get the raw pointer to the contained data
+    pub fn raw_ptr(&self) -> NonNull<()> {
+        self.data
+    }
+
+    /// Get the metadata of the contained value. As the type of the metadata is known, this is
+    /// safe, and allows for example getting the length of an erased slice.
+    pub fn meta(&self) -> M {
+        self.meta
+    }
+
+    /// Get a pointer to the value stored in this `Erased`
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_ptr<T: ?Sized + Pointee<Metadata = M>>(&self) -> NonNull<T> {
+        NonNull::from_raw_parts(self.data, self.meta)
+    }
+
+    /// Get a reference to the value stored in this `Erased`
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_ref<T: ?Sized + Pointee<Metadata = M>>(&self) -> &T {
+        self.reify_ptr().as_ref()
+    }
+
+    /// Get a mutable reference to the value stored in this `Erased`
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_mut<T: ?Sized + Pointee<Metadata = M>>(&mut self) -> &mut T {
+        self.reify_ptr().as_mut()
+    }
+
+    /// Convert this `Erased` back into a [`Box`] of the provided type
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_box<T: ?Sized + Pointee<Metadata = M>>(self) -> Box<T> {
+        let ptr = self.reify_ptr::<T>();
+        mem::forget(self);
+        Box::from_raw(ptr.as_ptr())
+    }
+}
+
+impl<M: ErasableMeta> fmt::Pointer for Erased<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.data, f)
+    }
+}
+
+impl<M: ErasableMeta + fmt::Debug> fmt::Debug for Erased<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Erased")
+            .field("data", &self.data)
+            .field("meta", &self.meta)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: ?Sized + Pointee<Metadata: ErasableMeta>> From<Box<T>> for Erased<T::Metadata> {
+    fn from(b: Box<T>) -> Self {
+        Erased::from_box(b)
+    }
+}
+
+impl<M: ErasableMeta> Drop for Erased<M> {
+    fn drop(&mut self) {
+        (self.drop)(self.data, self.meta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::rc::Rc;
+    use alloc::string::String;
+    use alloc::vec;
+    use core::ptr::DynMetadata;
+
+    #[test]
+    fn test_sized() {
+        let mut e = Erased::new(String::from("foo"));
+        unsafe { e.reify_mut::<String>() }.push_str("bar");
+        assert_eq!(unsafe { e.reify_ref::<String>() }, "foobar");
+        assert_eq!(*unsafe { e.reify_box::<String>() }, "foobar");
+    }
+
+    #[test]
+    fn test_slice() {
+        let e = Erased::from(vec![1u32, 2, 3].into_boxed_slice());
+        assert_eq!(e.meta(), 3);
+        assert_eq!(unsafe { e.reify_ref::<[u32]>() }, [1, 2, 3]);
+
+        let e: Erased<usize> = Erased::from(Box::<str>::from("hello"));
+        assert_eq!(unsafe { e.reify_ref::<str>() }, "hello");
+    }
+
+    #[test]
+    fn test_dyn() {
+        let e: Erased<DynMetadata<dyn fmt::Debug>> =
+            Erased::from(Box::new(1.5f32) as Box<dyn fmt::Debug>);
+        assert_eq!(
+            format!("{:?}", unsafe { e.reify_ref::<dyn fmt::Debug>() }),
+            "1.5"
+        );
+    }
+
+    #[test]
+    fn test_drop() {
+        let rc = Rc::new(());
+        let e = Erased::new(rc.clone());
+        assert_eq!(Rc::strong_count(&rc), 2);
+        drop(e);
+        assert_eq!(Rc::strong_count(&rc), 1);
+
+        let e = Erased::from(vec![rc.clone(), rc.clone()].into_boxed_slice());
+        assert_eq!(Rc::strong_count(&rc), 3);
+        drop(e);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn test_size() {
+        let word = mem::size_of::<usize>();
+        assert_eq!(mem::size_of::<Erased<()>>(), 2 * word);
+        assert_eq!(mem::size_of::<Erased<usize>>(), 3 * word);
+        assert_eq!(
+            mem::size_of::<Erased<DynMetadata<dyn fmt::Debug>>>(),
+            3 * word
+        );
+    }
+}
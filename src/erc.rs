@@ -0,0 +1,417 @@
+//! Reference counted erased values, which are one pointer wide with the default allocator
+
+use alloc::alloc::{handle_alloc_error, Global, Layout};
+use core::alloc::Allocator;
+use core::cell::Cell;
+use core::fmt;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{self, AtomicUsize, Ordering};
+
+/// The highest a reference count may go, as in `std`. Counts are only ever incremented by one
+/// past this, so they never come close to overflowing.
+const MAX_COUNT: usize = isize::MAX as usize;
+
+/// Abort the process, for when a reference count would overflow. This is `no_std`, so it panics
+/// while panicking, which aborts whether or not panics unwind.
+#[cold]
+fn abort() -> ! {
+    struct Abort;
+
+    impl Drop for Abort {
+        fn drop(&mut self) {
+            panic!("reference count overflowed");
+        }
+    }
+
+    let _abort = Abort;
+    panic!("reference count overflowed");
+}
+
+/// A reference count, either shared between threads or not
+trait RefCount {
+    fn one() -> Self;
+
+    fn get(&self) -> usize;
+
+    fn inc(&self);
+
+    /// Decrement the count, returning whether it reached zero
+    fn dec(&self) -> bool;
+}
+
+impl RefCount for Cell<usize> {
+    fn one() -> Self {
+        Cell::new(1)
+    }
+
+    fn get(&self) -> usize {
+        Cell::get(self)
+    }
+
+    fn inc(&self) {
+        let count = Cell::get(self) + 1;
+        if count > MAX_COUNT {
+            abort();
+        }
+        self.set(count);
+    }
+
+    fn dec(&self) -> bool {
+        self.set(self.get() - 1);
+        self.get() == 0
+    }
+}
+
+impl RefCount for AtomicUsize {
+    fn one() -> Self {
+        AtomicUsize::new(1)
+    }
+
+    fn get(&self) -> usize {
+        self.load(Ordering::Acquire)
+    }
+
+    fn inc(&self) {
+        // Other threads may increment before this aborts, but not the billions of times needed to
+        // overflow
+        if self.fetch_add(1, Ordering::Relaxed) > MAX_COUNT {
+            abort();
+        }
+    }
+
+    fn dec(&self) -> bool {
+        if self.fetch_sub(1, Ordering::Release) != 1 {
+            return false;
+        }
+        // Synchronize with all other decrements, so their uses of the value happen before drop
+        atomic::fence(Ordering::Acquire);
+        true
+    }
+}
+
+/// The control block at the start of every allocation, shared by all types of value
+#[repr(C)]
+struct Header<C> {
+    count: C,
+    /// Drops the value following the header in place
+    drop: unsafe fn(NonNull<Header<C>>),
+    /// The layout of the whole allocation, for freeing it
+    layout: Layout,
+}
+
+#[repr(C)]
+struct Inner<C, T> {
+    header: Header<C>,
+    data: T,
+}
+
+/// # Safety
+///
+/// The pointer must be to the header of an `Inner<C, T>`, whose value will not be used again
+unsafe fn drop_data<C, T>(ptr: NonNull<Header<C>>) {
+    ptr::drop_in_place(ptr::addr_of_mut!(
+        (*ptr.cast::<Inner<C, T>>().as_ptr()).data
+    ));
+}
+
+/// The implementation shared by [`ErasedRc`] and [`ErasedArc`], generic over the count
+struct RawRc<C: RefCount, A: Allocator> {
+    inner: NonNull<Header<C>>,
+    alloc: A,
+}
+
+impl<C: RefCount, A: Allocator> RawRc<C, A> {
+    fn new_in<T>(val: T, alloc: A) -> RawRc<C, A> {
+        let layout = Layout::new::<Inner<C, T>>();
+        let inner = alloc
+            .allocate(layout)
+            .unwrap_or_else(|_| handle_alloc_error(layout))
+            .cast::<Inner<C, T>>();
+        // SAFETY: The allocation is fresh, and valid for the layout of `Inner<C, T>`
+        unsafe {
+            inner.as_ptr().write(Inner {
+                header: Header {
+                    count: C::one(),
+                    drop: drop_data::<C, T>,
+                    layout,
+                },
+                data: val,
+            })
+        };
+        RawRc {
+            inner: inner.cast(),
+            alloc,
+        }
+    }
+
+    fn header(&self) -> &Header<C> {
+        // SAFETY: The header is valid while any reference to the allocation exists
+        unsafe { self.inner.as_ref() }
+    }
+
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored
+    unsafe fn reify_ref<T>(&self) -> &T {
+        &*ptr::addr_of!((*self.inner.cast::<Inner<C, T>>().as_ptr()).data)
+    }
+}
+
+impl<C: RefCount, A: Allocator + Clone> Clone for RawRc<C, A> {
+    fn clone(&self) -> Self {
+        self.header().count.inc();
+        RawRc {
+            inner: self.inner,
+            alloc: self.alloc.clone(),
+        }
+    }
+}
+
+impl<C: RefCount, A: Allocator> Drop for RawRc<C, A> {
+    fn drop(&mut self) {
+        if !self.header().count.dec() {
+            return;
+        }
+
+        let Header { drop, layout, .. } = *self.header();
+        // SAFETY: This was the last reference, so the value is never used again. The allocation
+        //         was made by our allocator, with the stored layout.
+        unsafe {
+            drop(self.inner);
+            self.alloc.deallocate(self.inner.cast(), layout);
+        }
+    }
+}
+
+/// A reference counted erased value, like an `Rc<T>` of unknown type. Creating one is safe, but
+/// converting it back into any type is unsafe as it requires the user to know the type stored in
+/// it. The counts, drop function, and value share one allocation, which is made and freed
+/// through the allocator `A`.
+///
+/// With the default [`Global`] allocator, this is one pointer wide. Only sized values may be
+/// stored, and there is no weak count.
+pub struct ErasedRc<A: Allocator = Global>(RawRc<Cell<usize>, A>);
+
+impl ErasedRc {
+    /// Create a new `ErasedRc` from a value
+    pub fn new<T>(val: T) -> ErasedRc {
+        ErasedRc::new_in(val, Global)
+    }
+}
+
+impl<A: Allocator> ErasedRc<A> {
+    /// Create a new `ErasedRc` from a value, allocated in the provided allocator
+    pub fn new_in<T>(val: T, alloc: A) -> ErasedRc<A> {
+        ErasedRc(RawRc::new_in(val, alloc))
+    }
+
+    /// Get the allocator of this `ErasedRc`
+    pub fn allocator(this: &Self) -> &A {
+        &this.0.alloc
+    }
+
+    /// Get the number of `ErasedRc`s sharing this value
+    pub fn strong_count(this: &Self) -> usize {
+        this.0.header().count.get()
+    }
+
+    /// Check whether two `ErasedRc`s share the same value
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.0.inner == other.0.inner
+    }
+
+    /// Get a reference to the value stored in this `ErasedRc`
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the rc
+    pub unsafe fn reify_ref<T>(&self) -> &T {
+        self.0.reify_ref()
+    }
+}
+
+impl<A: Allocator + Clone> Clone for ErasedRc<A> {
+    fn clone(&self) -> Self {
+        ErasedRc(self.0.clone())
+    }
+}
+
+impl<A: Allocator> fmt::Pointer for ErasedRc<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.0.inner, f)
+    }
+}
+
+impl<A: Allocator> fmt::Debug for ErasedRc<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedRc")
+            .field("strong", &ErasedRc::strong_count(self))
+            .finish_non_exhaustive()
+    }
+}
+
+/// A thread-safe reference counted erased value, like an `Arc<T>` of unknown type. As the type is
+/// erased, only values which are `Send + Sync` may be stored. Otherwise, this is the same as
+/// [`ErasedRc`].
+pub struct ErasedArc<A: Allocator = Global>(RawRc<AtomicUsize, A>);
+
+// SAFETY: Only `Send + Sync` values may be stored, and the count is atomic
+unsafe impl<A: Allocator + Send> Send for ErasedArc<A> {}
+// SAFETY: Only `Send + Sync` values may be stored, and the count is atomic
+unsafe impl<A: Allocator + Sync> Sync for ErasedArc<A> {}
+
+impl ErasedArc {
+    /// Create a new `ErasedArc` from a value
+    pub fn new<T: Send + Sync>(val: T) -> ErasedArc {
+        ErasedArc::new_in(val, Global)
+    }
+}
+
+impl<A: Allocator> ErasedArc<A> {
+    /// Create a new `ErasedArc` from a value, allocated in the provided allocator
+    pub fn new_in<T: Send + Sync>(val: T, alloc: A) -> ErasedArc<A> {
+        ErasedArc(RawRc::new_in(val, alloc))
+    }
+
+    /// Get the allocator of this `ErasedArc`
+    pub fn allocator(this: &Self) -> &A {
+        &this.0.alloc
+    }
+
+    /// Get the number of `ErasedArc`s sharing this value
+    pub fn strong_count(this: &Self) -> usize {
+        this.0.header().count.get()
+    }
+
+    /// Check whether two `ErasedArc`s share the same value
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.0.inner == other.0.inner
+    }
+
+    /// Get a reference to the value stored in this `ErasedArc`
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the arc
+    pub unsafe fn reify_ref<T>(&self) -> &T {
+        self.0.reify_ref()
+    }
+}
+
+impl<A: Allocator + Clone> Clone for ErasedArc<A> {
+    fn clone(&self) -> Self {
+        ErasedArc(self.0.clone())
+    }
+}
+
+impl<A: Allocator> fmt::Pointer for ErasedArc<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.0.inner, f)
+    }
+}
+
+impl<A: Allocator> fmt::Debug for ErasedArc<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedArc")
+            .field("strong", &ErasedArc::strong_count(self))
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use alloc::rc::Rc;
+    use alloc::string::String;
+    use core::alloc::AllocError;
+    use core::mem;
+
+    /// An allocator counting the allocations and frees made through it
+    #[derive(Copy, Clone)]
+    struct Tracking<'a> {
+        allocs: &'a Cell<usize>,
+        frees: &'a Cell<usize>,
+    }
+
+    unsafe impl Allocator for Tracking<'_> {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.allocs.set(self.allocs.get() + 1);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.frees.set(self.frees.get() + 1);
+            Global.deallocate(ptr, layout)
+        }
+    }
+
+    #[test]
+    fn test_rc_size() {
+        assert_eq!(mem::size_of::<ErasedRc>(), mem::size_of::<usize>());
+        assert_eq!(mem::size_of::<ErasedArc>(), mem::size_of::<usize>());
+    }
+
+    #[test]
+    fn test_rc_clone() {
+        let rc = ErasedRc::new(String::from("foo"));
+        let rc2 = rc.clone();
+        assert!(ErasedRc::ptr_eq(&rc, &rc2));
+        assert_eq!(ErasedRc::strong_count(&rc), 2);
+        assert_eq!(unsafe { rc2.reify_ref::<String>() }, "foo");
+
+        drop(rc);
+        assert_eq!(ErasedRc::strong_count(&rc2), 1);
+        assert_eq!(unsafe { rc2.reify_ref::<String>() }, "foo");
+    }
+
+    #[test]
+    fn test_rc_drop_once() {
+        let count = Rc::new(());
+        let rc = ErasedRc::new(count.clone());
+        let clones = [rc.clone(), rc.clone()];
+        assert_eq!(Rc::strong_count(&count), 2);
+        drop(rc);
+        drop(clones);
+        assert_eq!(Rc::strong_count(&count), 1);
+    }
+
+    #[test]
+    fn test_rc_allocator() {
+        let allocs = Cell::new(0);
+        let frees = Cell::new(0);
+        let alloc = Tracking {
+            allocs: &allocs,
+            frees: &frees,
+        };
+
+        let rc = ErasedRc::new_in(String::from("foo"), alloc);
+        let rc2 = rc.clone();
+        drop(rc);
+        assert_eq!((allocs.get(), frees.get()), (1, 0));
+        drop(rc2);
+        assert_eq!((allocs.get(), frees.get()), (1, 1));
+
+        let arc = ErasedArc::new_in([1u64; 4], alloc);
+        let arc2 = arc.clone();
+        assert_eq!(unsafe { arc2.reify_ref::<[u64; 4]>() }, &[1; 4]);
+        drop((arc, arc2));
+        assert_eq!((allocs.get(), frees.get()), (2, 2));
+    }
+
+    #[test]
+    fn test_arc_threads() {
+        let arc = ErasedArc::new(String::from("foo"));
+        let handles = (0..4)
+            .map(|_| {
+                let arc = arc.clone();
+                std::thread::spawn(move || unsafe { arc.reify_ref::<String>() }.len())
+            })
+            .collect::<alloc::vec::Vec<_>>();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 3);
+        }
+        assert_eq!(ErasedArc::strong_count(&arc), 1);
+    }
+}
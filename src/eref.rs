@@ -1,17 +1,21 @@
-//! Erased reference types, all are 3 pointers wide
+//! Erased reference types, all are 2 pointers wide
 
 use core::fmt;
 use core::marker::PhantomData;
 use core::ptr::Pointee;
 
+use crate::meta::ErasableMeta;
 use crate::ErasedNonNull;
 
 /// An erased reference, referencing a (possibly unsized) value of unknown type. Creating one is
 /// safe, but converting it back into any type is unsafe as it requires the user to know the type
 /// stored behind the reference.
 ///
-/// This type will always be three pointers wide, even for sized types, due to needing to store
+/// This type will always be two pointers wide, even for sized types, due to needing to store
 /// an unknown metadata.
+///
+/// Like `&'a T`, this type is covariant in `'a`, so an `ErasedRef<'long>` may be used where an
+/// `ErasedRef<'short>` is expected. [`shorten`](Self::shorten) does the same explicitly.
 pub struct ErasedRef<'a> {
     ptr: ErasedNonNull,
     _phantom: PhantomData<&'a ()>,
@@ -19,7 +23,7 @@ pub struct ErasedRef<'a> {
 
 impl<'a> ErasedRef<'a> {
     /// Create a new `ErasedRef` from a reference
-    pub fn new<T: ?Sized>(val: &'a T) -> ErasedRef<'a> {
+    pub fn new<T: ?Sized + Pointee<Metadata: ErasableMeta>>(val: &'a T) -> ErasedRef<'a> {
         ErasedRef {
             ptr: ErasedNonNull::from(val),
             _phantom: PhantomData,
@@ -31,6 +35,15 @@ impl<'a> ErasedRef<'a> {
         &self.ptr
     }
 
+    /// Shorten the lifetime of this `ErasedRef`. This is never required, as the lifetime is
+    /// covariant, but may be clearer than relying on subtyping.
+    pub fn shorten<'b>(self) -> ErasedRef<'b>
+    where
+        'a: 'b,
+    {
+        self
+    }
+
     /// Get back the reference stored in this `ErasedRef`
     ///
     /// # Safety
@@ -59,16 +72,20 @@ impl fmt::Debug for ErasedRef<'_> {
 /// one is safe, but converting it back into any type is unsafe as it requires the user to know the
 /// type stored behind the reference.
 ///
-/// This type will always be three pointers wide, even for sized types, due to needing to store
+/// This type will always be two pointers wide, even for sized types, due to needing to store
 /// an unknown metadata.
+///
+/// Like `&'a mut T`, this type is covariant in `'a`. The erased type can't be changed through it,
+/// so there is nothing for it to be invariant over. To use it for a shorter lifetime without
+/// giving it up, [`reborrow`](Self::reborrow) it.
 pub struct ErasedMut<'a> {
     ptr: ErasedNonNull,
     _phantom: PhantomData<&'a mut ()>,
 }
 
 impl<'a> ErasedMut<'a> {
-    /// Create a new `ErasedMute` from a reference
-    pub fn new<T: ?Sized>(val: &'a mut T) -> ErasedMut<'a> {
+    /// Create a new `ErasedMut` from a reference
+    pub fn new<T: ?Sized + Pointee<Metadata: ErasableMeta>>(val: &'a mut T) -> ErasedMut<'a> {
         ErasedMut {
             ptr: ErasedNonNull::from(val),
             _phantom: PhantomData,
@@ -80,12 +97,64 @@ impl<'a> ErasedMut<'a> {
         &self.ptr
     }
 
-    /// Get back the mutable reference stored in this `ErasedRef`
+    /// Reborrow this `ErasedMut`, creating a new erased mutable reference which borrows this one,
+    /// like `&mut *val` does for normal references.
+    ///
+    /// The reborrow cannot outlive the original:
+    ///
+    /// ```compile_fail
+    /// # use craft_eraser::ErasedMut;
+    /// let mut val = 1;
+    /// let mut outer = ErasedMut::new(&mut val);
+    /// let inner = outer.reborrow();
+    /// drop(outer);
+    /// drop(inner);
+    /// ```
+    pub fn reborrow(&mut self) -> ErasedMut<'_> {
+        ErasedMut {
+            ptr: self.ptr,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Reborrow this `ErasedMut` as a shared erased reference which borrows this one, like
+    /// `&*val` does for normal references.
+    pub fn reborrow_shared(&self) -> ErasedRef<'_> {
+        ErasedRef {
+            ptr: self.ptr,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Convert this `ErasedMut` into a shared erased reference, keeping the full lifetime
+    pub fn into_shared(self) -> ErasedRef<'a> {
+        ErasedRef {
+            ptr: self.ptr,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Get a shared erased reference borrowing this `ErasedMut`. This is the same as
+    /// [`reborrow_shared`](Self::reborrow_shared).
+    pub fn as_shared(&self) -> ErasedRef<'_> {
+        self.reborrow_shared()
+    }
+
+    /// Get a shared reference to the value referenced by this `ErasedMut`
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the reference
+    pub unsafe fn reify_shared<T: ?Sized + Pointee>(&self) -> &T {
+        self.ptr.reify_ptr::<T>().as_ref()
+    }
+
+    /// Get back the mutable reference stored in this `ErasedMut`
     ///
     /// # Safety
     ///
     /// The provided `T` must be the same type as originally stored in the reference
-    pub unsafe fn reify_ref<T: ?Sized + Pointee>(&mut self) -> &mut T {
+    pub unsafe fn reify_mut<T: ?Sized + Pointee>(&mut self) -> &mut T {
         self.ptr.reify_ptr::<T>().as_mut()
     }
 }
@@ -98,8 +167,110 @@ impl fmt::Pointer for ErasedMut<'_> {
 
 impl fmt::Debug for ErasedMut<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("ErasedRef")
+        f.debug_struct("ErasedMut")
             .field("ptr", &self.ptr)
             .finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::String;
+
+    fn shorten_ref<'short, 'long: 'short>(val: ErasedRef<'long>) -> ErasedRef<'short> {
+        val
+    }
+
+    fn shorten_mut<'short, 'long: 'short>(val: ErasedMut<'long>) -> ErasedMut<'short> {
+        val
+    }
+
+    fn add_one(mut val: ErasedMut<'_>) {
+        *unsafe { val.reify_mut::<i32>() } += 1;
+    }
+
+    #[test]
+    fn test_reborrow() {
+        let mut item = 1;
+        let mut em = ErasedMut::new(&mut item);
+
+        add_one(em.reborrow());
+        add_one(em.reborrow());
+        assert_eq!(*unsafe { em.reborrow_shared().reify_ref::<i32>() }, 3);
+        assert_eq!(*unsafe { em.reify_mut::<i32>() }, 3);
+    }
+
+    #[test]
+    fn test_reify_shared() {
+        let mut item = 5;
+        let em = ErasedMut::new(&mut item);
+        let em_ref = &em;
+
+        assert_eq!(*unsafe { em_ref.reify_shared::<i32>() }, 5);
+        assert_eq!(*unsafe { em.reify_shared::<i32>() }, 5);
+    }
+
+    #[test]
+    fn test_into_shared() {
+        let mut item = String::from("foo");
+        let mut em = ErasedMut::new(item.as_mut_str());
+
+        unsafe { em.reify_mut::<str>() }.make_ascii_uppercase();
+        assert_eq!(unsafe { em.as_shared().reify_ref::<str>() }, "FOO");
+
+        let er = em.into_shared();
+        assert_eq!(unsafe { er.reify_ref::<str>() }, "FOO");
+    }
+
+    #[test]
+    fn test_fmt() {
+        let mut item = 1;
+        assert!(format!("{:?}", ErasedRef::new(&item)).starts_with("ErasedRef"));
+
+        let em = ErasedMut::new(&mut item);
+        assert!(format!("{:?}", em).starts_with("ErasedMut"));
+        assert_eq!(format!("{:p}", em), format!("{:p}", em.as_ptr().raw_ptr()));
+    }
+
+    #[test]
+    fn test_ref_slice() {
+        let item = [1, 2, 3];
+        let er = ErasedRef::new(&item[..]);
+        assert_eq!(unsafe { er.reify_ref::<[i32]>() }, [1, 2, 3]);
+
+        let er = ErasedRef::new(&item[1..]);
+        assert_eq!(unsafe { er.reify_ref::<[i32]>() }, [2, 3]);
+    }
+
+    #[test]
+    fn test_mut_slice() {
+        let mut item = [1, 2, 3];
+        let mut em = ErasedMut::new(&mut item as &mut [i32]);
+
+        let slice = unsafe { em.reify_mut::<[i32]>() };
+        assert_eq!(slice.len(), 3);
+        slice[1] = 5;
+        assert_eq!(item, [1, 5, 3]);
+    }
+
+    #[test]
+    fn test_variance() {
+        static ITEM: i32 = 5;
+        let er: ErasedRef<'static> = ErasedRef::new(&ITEM);
+
+        let local = 1;
+        // Both references must have the same lifetime, which is shortened implicitly
+        let refs = [er, ErasedRef::new(&local)];
+        assert_eq!(*unsafe { refs[0].reify_ref::<i32>() }, 5);
+
+        let short = shorten_ref(ErasedRef::new(&ITEM).shorten());
+        assert_eq!(*unsafe { short.reify_ref::<i32>() }, 5);
+
+        let mut item = 2;
+        let mut em = shorten_mut(ErasedMut::new(&mut item));
+        add_one(em.reborrow());
+        assert_eq!(*unsafe { em.reify_mut::<i32>() }, 3);
+    }
+}
@@ -0,0 +1,211 @@
+//! A vector of erased values which all share the same type
+
+use alloc::alloc::{handle_alloc_error, Layout};
+use core::ptr::{self, NonNull};
+use core::{fmt, mem};
+
+/// # Safety
+///
+/// The pointer must point to a valid `T`, which will not be used again
+unsafe fn drop_elem<T>(ptr: *mut u8) {
+    ptr::drop_in_place(ptr.cast::<T>());
+}
+
+/// A vector storing values of a single erased type in one contiguous allocation. This avoids the
+/// per-value allocation and header of a `Vec<ThinErasedBox>`, when all values are known to be of
+/// the same type.
+///
+/// The layout of the element type is stored, and checked when pushing, but the type itself isn't,
+/// so pushing and reading values is unsafe.
+pub struct ErasedVec {
+    data: NonNull<u8>,
+    len: usize,
+    cap: usize,
+    layout: Layout,
+    drop: unsafe fn(*mut u8),
+}
+
+impl ErasedVec {
+    /// Create a new, empty `ErasedVec` for values of type `T`. This doesn't allocate.
+    pub fn new<T>() -> ErasedVec {
+        let layout = Layout::new::<T>();
+        ErasedVec {
+            // SAFETY: Alignment is always non-zero
+            data: unsafe { NonNull::new_unchecked(ptr::without_provenance_mut(layout.align())) },
+            len: 0,
+            cap: if layout.size() == 0 { usize::MAX } else { 0 },
+            layout,
+            drop: drop_elem::<T>,
+        }
+    }
+
+    /// Get the number of values in this `ErasedVec`
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether this `ErasedVec` contains no values
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn slot(&self, idx: usize) -> *mut u8 {
+        self.data.as_ptr().wrapping_add(idx * self.layout.size())
+    }
+
+    fn array_layout(&self, cap: usize) -> Layout {
+        let size = self
+            .layout
+            .size()
+            .checked_mul(cap)
+            .expect("ErasedVec capacity overflow");
+        Layout::from_size_align(size, self.layout.align()).expect("ErasedVec capacity overflow")
+    }
+
+    fn grow(&mut self) {
+        let new_cap = usize::max(4, self.cap * 2);
+        let new_layout = self.array_layout(new_cap);
+
+        let new_data = if self.cap == 0 {
+            // SAFETY: Layout isn't zero-sized, as ZSTs never need to grow
+            unsafe { alloc::alloc::alloc(new_layout) }
+        } else {
+            // SAFETY: Our data was allocated with the layout of our current capacity
+            unsafe {
+                alloc::alloc::realloc(
+                    self.data.as_ptr(),
+                    self.array_layout(self.cap),
+                    new_layout.size(),
+                )
+            }
+        };
+
+        self.data = NonNull::new(new_data).unwrap_or_else(|| handle_alloc_error(new_layout));
+        self.cap = new_cap;
+    }
+
+    /// Push a value onto the end of this `ErasedVec`
+    ///
+    /// # Panics
+    ///
+    /// If the layout of `T` doesn't match that of the vector's element type
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type the vector was created for
+    pub unsafe fn push<T>(&mut self, val: T) {
+        assert_eq!(
+            Layout::new::<T>(),
+            self.layout,
+            "ErasedVec element layout mismatch"
+        );
+        if self.len == self.cap {
+            self.grow();
+        }
+        self.slot(self.len).cast::<T>().write(val);
+        self.len += 1;
+    }
+
+    /// Get a reference to the value at the provided index
+    ///
+    /// # Panics
+    ///
+    /// If the index is out of bounds
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type the vector was created for
+    pub unsafe fn get<T>(&self, idx: usize) -> &T {
+        assert!(idx < self.len, "ErasedVec index out of bounds");
+        &*self.slot(idx).cast::<T>()
+    }
+
+    /// Get a mutable reference to the value at the provided index
+    ///
+    /// # Panics
+    ///
+    /// If the index is out of bounds
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type the vector was created for
+    pub unsafe fn get_mut<T>(&mut self, idx: usize) -> &mut T {
+        assert!(idx < self.len, "ErasedVec index out of bounds");
+        &mut *self.slot(idx).cast::<T>()
+    }
+}
+
+impl fmt::Debug for ErasedVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasedVec")
+            .field("len", &self.len)
+            .field("layout", &self.layout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for ErasedVec {
+    fn drop(&mut self) {
+        // Forget the values first, so a panicking destructor leaks rather than double drops
+        let len = mem::replace(&mut self.len, 0);
+        for idx in 0..len {
+            // SAFETY: Every slot below the length holds a value of the element type
+            unsafe { (self.drop)(self.slot(idx)) };
+        }
+
+        if self.layout.size() != 0 && self.cap != 0 {
+            // SAFETY: Our data was allocated with the layout of our current capacity
+            unsafe { alloc::alloc::dealloc(self.data.as_ptr(), self.array_layout(self.cap)) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::rc::Rc;
+    use alloc::string::String;
+
+    #[test]
+    fn test_push_get() {
+        let mut ev = ErasedVec::new::<String>();
+        for i in 0..10 {
+            unsafe { ev.push(format!("item {}", i)) };
+        }
+        unsafe { ev.get_mut::<String>(3) }.push('!');
+
+        assert_eq!(ev.len(), 10);
+        assert_eq!(unsafe { ev.get::<String>(0) }, "item 0");
+        assert_eq!(unsafe { ev.get::<String>(3) }, "item 3!");
+        assert_eq!(unsafe { ev.get::<String>(9) }, "item 9");
+    }
+
+    #[test]
+    fn test_drop() {
+        let rc = Rc::new(());
+        let mut ev = ErasedVec::new::<Rc<()>>();
+        for _ in 0..5 {
+            unsafe { ev.push(rc.clone()) };
+        }
+        assert_eq!(Rc::strong_count(&rc), 6);
+        drop(ev);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn test_zst() {
+        let mut ev = ErasedVec::new::<()>();
+        unsafe { ev.push(()) };
+        unsafe { ev.push(()) };
+        assert_eq!(ev.len(), 2);
+        assert_eq!(unsafe { ev.get::<()>(1) }, &());
+    }
+
+    #[test]
+    #[should_panic = "ErasedVec element layout mismatch"]
+    fn test_layout_mismatch() {
+        let mut ev = ErasedVec::new::<u8>();
+        unsafe { ev.push(1u32) };
+    }
+}
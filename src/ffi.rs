@@ -0,0 +1,161 @@
+//! Stable C-ABI representations of erased boxes, for passing them through FFI
+
+use alloc::boxed::Box;
+use core::ffi::c_void;
+use core::mem;
+use core::ptr::NonNull;
+
+use crate::{ErasedBox, ThinErasedBox};
+
+/// A `#[repr(C)]` form of an [`ErasedBox`], which may be passed to and from C code. Create one
+/// with [`ErasedBox::into_c`], and convert it back with [`ErasedBox::from_c`].
+///
+/// C code must not interpret `data` or `meta`, only store them and pass them back, either to
+/// Rust or to `drop`. Calling `drop` frees the value, after which the struct must not be used.
+#[repr(C)]
+#[derive(Debug)]
+pub struct CErasedBox {
+    /// Pointer to the erased value
+    pub data: *mut c_void,
+    /// Opaque pointer to the rest of the box
+    pub meta: *mut c_void,
+    /// Drop the value, given the `data` and `meta` pointers. Must only be called once, with the
+    /// unmodified pointers of this struct.
+    pub drop: Option<unsafe extern "C" fn(*mut c_void, *mut c_void)>,
+}
+
+/// # Safety
+///
+/// `meta` must be from a [`CErasedBox`] created by `into_c`, unmodified and not already dropped
+/// or converted back
+unsafe extern "C" fn drop_c(_: *mut c_void, meta: *mut c_void) {
+    // SAFETY: `meta` is from `Box::into_raw` in `into_c`, by safety constraints
+    drop(unsafe { Box::from_raw(meta.cast::<ErasedBox>()) });
+}
+
+impl ErasedBox {
+    /// Convert this `ErasedBox` into a [`CErasedBox`], which may be passed through FFI. The
+    /// value stays in its allocation, but the rest of the box is moved to the heap so it fits
+    /// behind a single pointer.
+    pub fn into_c(self) -> CErasedBox {
+        let data = self.raw_ptr().as_ptr().cast::<c_void>();
+        CErasedBox {
+            data,
+            meta: Box::into_raw(Box::new(self)).cast(),
+            drop: Some(drop_c),
+        }
+    }
+
+    /// Convert a [`CErasedBox`] back into an `ErasedBox`
+    ///
+    /// # Safety
+    ///
+    /// The `CErasedBox` must have been created by [`into_c`](Self::into_c), with its pointers
+    /// unmodified, and not already dropped or converted back
+    pub unsafe fn from_c(c: CErasedBox) -> ErasedBox {
+        *Box::from_raw(c.meta.cast::<ErasedBox>())
+    }
+}
+
+/// A `#[repr(C)]` form of a [`ThinErasedBox`], with a stable layout so it may be passed across a
+/// dynamic library boundary. Convert one with [`From`], and back with
+/// [`ThinErasedBox::from_ffi`].
+///
+/// `inner` points to the allocation of the box, which stores the drop function of the module
+/// that created it. Calling `drop` with `inner` runs that function and frees the value, so it may
+/// be dropped from any module, but reifying it still requires knowing the stored type.
+#[repr(C)]
+#[derive(Debug)]
+pub struct ErasedBoxFfi {
+    /// Opaque pointer to the allocation of the box
+    pub inner: *mut c_void,
+    /// Drop the value, given the `inner` pointer. Must only be called once, with the unmodified
+    /// pointer of this struct.
+    pub drop: unsafe extern "C" fn(*mut c_void),
+}
+
+/// # Safety
+///
+/// `inner` must be from an [`ErasedBoxFfi`] created from a `ThinErasedBox`, unmodified and not
+/// already dropped or converted back
+unsafe extern "C" fn drop_thin_ffi(inner: *mut c_void) {
+    // SAFETY: `inner` is from a `ThinErasedBox`, unmodified by safety constraints
+    drop(ThinErasedBox {
+        inner: unsafe { NonNull::new_unchecked(inner.cast()) },
+    });
+}
+
+impl From<ThinErasedBox> for ErasedBoxFfi {
+    fn from(eb: ThinErasedBox) -> Self {
+        let inner = eb.inner.as_ptr().cast::<c_void>();
+        mem::forget(eb);
+        ErasedBoxFfi {
+            inner,
+            drop: drop_thin_ffi,
+        }
+    }
+}
+
+impl ThinErasedBox {
+    /// Convert an [`ErasedBoxFfi`] back into a `ThinErasedBox`
+    ///
+    /// # Safety
+    ///
+    /// The `ErasedBoxFfi` must have been created from a `ThinErasedBox`, with its pointer
+    /// unmodified, and not already dropped or converted back
+    pub unsafe fn from_ffi(ffi: ErasedBoxFfi) -> ThinErasedBox {
+        ThinErasedBox {
+            inner: NonNull::new_unchecked(ffi.inner.cast()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+    use alloc::string::String;
+
+    extern "C" fn roundtrip(c: CErasedBox) -> CErasedBox {
+        c
+    }
+
+    extern "C" fn c_drop(c: CErasedBox) {
+        // SAFETY: The pointers are passed back unmodified, and the struct is consumed
+        unsafe { (c.drop.unwrap())(c.data, c.meta) }
+    }
+
+    #[test]
+    fn test_c_roundtrip() {
+        let c = roundtrip(ErasedBox::new_any(String::from("foo")).into_c());
+        assert_eq!(unsafe { &*c.data.cast::<String>() }, "foo");
+
+        let eb = unsafe { ErasedBox::from_c(c) };
+        assert_eq!(eb.downcast_ref::<String>().unwrap(), "foo");
+    }
+
+    #[test]
+    fn test_c_drop() {
+        let rc = Rc::new(());
+        c_drop(ErasedBox::new(rc.clone()).into_c());
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    extern "C" fn ffi_roundtrip(ffi: ErasedBoxFfi) -> ErasedBoxFfi {
+        ffi
+    }
+
+    #[test]
+    fn test_ffi_roundtrip() {
+        let ffi = ffi_roundtrip(ErasedBoxFfi::from(ThinErasedBox::new([1u16, 2, 3])));
+        let eb = unsafe { ThinErasedBox::from_ffi(ffi) };
+        assert_eq!(unsafe { eb.reify_ref::<[u16; 3]>() }, &[1, 2, 3]);
+
+        let rc = Rc::new(());
+        let ffi = ffi_roundtrip(ThinErasedBox::new(rc.clone()).into());
+        assert_eq!(Rc::strong_count(&rc), 2);
+        // SAFETY: The pointer is passed back unmodified, and `ffi` isn't used afterwards
+        unsafe { (ffi.drop)(ffi.inner) };
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
@@ -9,22 +9,51 @@
 //! a trade-off, there is no safe way to retrieve the data, as the user must already know the
 //! type and lifetimes involved and verify them without the help of the compiler.
 //!
-//! # Erased Pointer
+//! The basic boxes are [`ErasedBox`], which stores its metadata alongside the data pointer, and
+//! [`ThinErasedBox`], which stores it in the allocation to stay one pointer wide. Variations on
+//! them include [`SmallErasedBox`] storing small values inline, [`TypedErasedBox`] checked by a
+//! key type, [`Erased`] for a statically known kind of metadata, [`ErasedCell`] with runtime
+//! borrow tracking, [`ErasedCow`], and the reference counted [`ErasedRc`] and [`ErasedArc`].
+//! Boxes can be passed through FFI as a [`CErasedBox`] or [`ErasedBoxFfi`].
 //!
-//! The unowned equivalent to an erased box. Basically just a pointer-meta pair, that ensures
-//! the meta is handled correctly on destruction.
+//! # Erased Pointers
+//!
+//! The unowned equivalent to an erased box. [`ErasedPtr`] and [`ErasedNonNull`] are just a
+//! pointer-meta pair, two pointers wide and [`Copy`], with the metadata stored inline so there is
+//! nothing to free. [`ThinErasedPtr`] is one pointer wide, for sized types only, and
+//! [`ErasedRef`] and [`ErasedMut`] add a lifetime like a reference.
+//!
+//! # Collections
+//!
+//! [`ErasedVec`] stores many values of one erased type, [`ErasedMap`] one value of each type, and
+//! [`ErasedArena`] bump-allocates values of any type to be freed together. The [`collect`] module
+//! has helpers for collections of erased values.
+//!
+//! # Utilities
+//!
+//! The [`erase!`] macro and [`AsErased`] trait erase values without naming the erased type, and
+//! the [`Reify`] and [`ReifyOwned`] traits allow code generic over the erased types. A
+//! [`VtableRegistry`] allows reifying values of known type as trait objects. The [`meta`] module
+//! covers how metadata is stored.
 
-#![feature(ptr_metadata)]
+#![feature(
+    ptr_metadata,
+    allocator_api,
+    layout_for_ptr,
+    strict_provenance_lints,
+    unsize
+)]
 #![warn(
     missing_docs,
     elided_lifetimes_in_paths,
     explicit_outlives_requirements,
     missing_abi,
     noop_method_call,
-    pointer_structural_match,
     semicolon_in_expressions_from_macros,
     unused_import_braces,
     unused_lifetimes,
+    fuzzy_provenance_casts,
+    lossy_provenance_casts,
     clippy::cargo,
     clippy::missing_panics_doc,
     clippy::doc_markdown,
@@ -36,12 +65,119 @@
 
 extern crate alloc;
 
+pub mod arena;
+pub mod as_erased;
+#[cfg(feature = "checksum")]
+mod checksum;
+pub mod collect;
 pub mod ebox;
+pub mod ecell;
+pub mod ecow;
+pub mod emap;
 pub mod eptr;
+pub mod erased;
+pub mod erc;
 pub mod eref;
+pub mod evec;
+pub mod ffi;
+pub mod meta;
+pub mod registry;
+pub mod reify;
+pub mod small_ebox;
 pub mod thin_ebox;
+pub mod typed_ebox;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::boxed::Box;
+}
 
+/// Erase a value into an [`ErasedBox`] or [`ThinErasedBox`], optionally coercing it to an unsized
+/// type first. The output type is inferred, so it must be known from context.
+///
+/// `erase!(val)` erases a sized value, while `erase!(val as Type)` first coerces the boxed value
+/// to `Box<Type>`, such as `dyn Trait` or a slice. Expressions containing `as` themselves should
+/// be wrapped in parentheses.
+///
+/// ```
+/// # use core::fmt::Debug;
+/// # use craft_eraser::{erase, ErasedBox, ThinErasedBox};
+/// let eb: ThinErasedBox = erase!(1.5 as dyn Debug);
+/// let eb: ErasedBox = erase!([1, 2, 3] as [i32]);
+/// let eb: ErasedBox = erase!(String::from("foo"));
+/// ```
+///
+/// Invalid coercions fail to compile:
+///
+/// ```compile_fail
+/// # use craft_eraser::{erase, ErasedBox};
+/// let eb: ErasedBox = erase!(1u8 as [u8]);
+/// ```
+#[macro_export]
+macro_rules! erase {
+    (@munch [$($val:tt)*] as $($ty:tt)+) => {
+        ::core::convert::From::from(
+            $crate::__private::Box::new($($val)*) as $crate::__private::Box<$($ty)+>
+        )
+    };
+    (@munch [$($val:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::erase!(@munch [$($val)* $next] $($rest)*)
+    };
+    (@munch [$($val:tt)*]) => {
+        ::core::convert::From::from($crate::__private::Box::new($($val)*))
+    };
+    ($($tokens:tt)+) => {
+        $crate::erase!(@munch [] $($tokens)+)
+    };
+}
+
+pub use arena::ErasedArena;
+pub use as_erased::AsErased;
 pub use ebox::ErasedBox;
-pub use eptr::{ErasedNonNull, ErasedPtr};
+pub use ecell::ErasedCell;
+pub use ecow::ErasedCow;
+pub use emap::ErasedMap;
+pub use eptr::{ErasedNonNull, ErasedPtr, ThinErasedPtr};
+pub use erased::Erased;
+pub use erc::{ErasedArc, ErasedRc};
 pub use eref::{ErasedMut, ErasedRef};
-pub use thin_ebox::ThinErasedBox;
+pub use evec::ErasedVec;
+pub use ffi::{CErasedBox, ErasedBoxFfi};
+pub use meta::{ErasableMeta, RawMeta};
+pub use registry::VtableRegistry;
+pub use reify::{Reify, ReifyOwned};
+pub use small_ebox::SmallErasedBox;
+pub use thin_ebox::{RefillGuard, ThinErasedBox, ThinErasedRef};
+pub use typed_ebox::{erase_typed, ErasedKey, TypedErasedBox};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::String;
+    use core::fmt::Debug;
+
+    #[test]
+    fn test_erase_dyn() {
+        let eb: ThinErasedBox = erase!(123.45 as dyn Debug);
+        assert_eq!(
+            format!("{:?}", unsafe { eb.reify_ref::<dyn Debug>() }),
+            "123.45"
+        );
+    }
+
+    #[test]
+    fn test_erase_slice() {
+        let eb: ErasedBox = erase!([1, 2, 3] as [i32]);
+        assert_eq!(unsafe { eb.reify_ref::<[i32]>() }, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_erase_sized() {
+        let eb: ErasedBox = erase!(String::from("foo"));
+        assert_eq!(unsafe { eb.reify_ref::<String>() }, "foo");
+
+        let eb: ThinErasedBox = erase!((1u8 as u32));
+        assert_eq!(*unsafe { eb.reify_ref::<u32>() }, 1);
+    }
+}
@@ -0,0 +1,158 @@
+//! Support for the pointer metadata types which can be stored in erased types
+//!
+//! The set of metadata types is currently exhaustive for every type in the language: sized types
+//! and extern types have `()` metadata, slices and `str` have `usize` metadata, and trait objects
+//! have [`DynMetadata`] metadata. All of these implement [`ErasableMeta`], so any type may be
+//! erased. Should a new kind of metadata be added to the language, types using it will fail to
+//! erase with an unsatisfied `ErasableMeta` bound, rather than an error deep inside the
+//! implementation.
+
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::mem::{self, MaybeUninit};
+use core::ptr::{DynMetadata, NonNull, Pointee};
+
+mod sealed {
+    /// The kinds of metadata which may be erased, one for each [`ErasableMeta`](super::ErasableMeta)
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    #[repr(u8)]
+    pub enum MetaKind {
+        Sized,
+        Slice,
+        Dyn,
+    }
+
+    pub trait Sealed {
+        const KIND: MetaKind;
+    }
+}
+
+pub(crate) use sealed::MetaKind;
+
+/// A pointer metadata type supported by the erased types in this crate. This trait is sealed and
+/// cannot be implemented outside this crate.
+pub trait ErasableMeta: sealed::Sealed + Copy + Send + Sync + Unpin {}
+
+impl sealed::Sealed for () {
+    const KIND: MetaKind = MetaKind::Sized;
+}
+impl ErasableMeta for () {}
+
+impl sealed::Sealed for usize {
+    const KIND: MetaKind = MetaKind::Slice;
+}
+impl ErasableMeta for usize {}
+
+impl<Dyn: ?Sized> sealed::Sealed for DynMetadata<Dyn> {
+    const KIND: MetaKind = MetaKind::Dyn;
+}
+impl<Dyn: ?Sized> ErasableMeta for DynMetadata<Dyn> {}
+
+/// Get the kind of the metadata of a pointer to `T`
+pub(crate) const fn meta_kind<T: ?Sized + Pointee<Metadata: ErasableMeta>>() -> MetaKind {
+    <T::Metadata as sealed::Sealed>::KIND
+}
+
+impl MetaKind {
+    /// Get the kind with the provided discriminant, if there is one
+    pub(crate) fn from_raw(raw: u8) -> Option<MetaKind> {
+        [MetaKind::Sized, MetaKind::Slice, MetaKind::Dyn]
+            .into_iter()
+            .find(|kind| *kind as u8 == raw)
+    }
+
+    /// Check whether erased metadata holds a valid value of this kind. Sized metadata is always
+    /// zeroed, and trait object metadata is a non-null, aligned, vtable pointer.
+    pub(crate) fn is_valid(self, meta: RawMeta) -> bool {
+        match self {
+            MetaKind::Sized => meta.addr() == 0,
+            MetaKind::Slice => true,
+            MetaKind::Dyn => {
+                meta.addr() != 0 && meta.addr().is_multiple_of(mem::align_of::<usize>())
+            }
+        }
+    }
+}
+
+/// The metadata of an erased pointer, stored inline. This is one pointer wide, which is large
+/// enough to store any [`ErasableMeta`], and preserves the provenance of vtable pointers.
+///
+/// Comparing two `RawMeta` compares their raw bytes, which is only meaningful if both were
+/// created for the same type. The ordering is arbitrary, but consistent with equality.
+#[derive(Copy, Clone)]
+pub struct RawMeta(MaybeUninit<*const ()>);
+
+impl RawMeta {
+    /// Erase the metadata of a pointer to `T`
+    pub fn new<T: ?Sized + Pointee<Metadata: ErasableMeta>>(meta: T::Metadata) -> RawMeta {
+        const {
+            assert!(mem::size_of::<T::Metadata>() <= mem::size_of::<RawMeta>());
+            assert!(mem::align_of::<T::Metadata>() <= mem::align_of::<RawMeta>());
+        }
+
+        // Zeroed, so any bytes not covered by the metadata are still initialized
+        let mut out = RawMeta(MaybeUninit::zeroed());
+        // SAFETY: We just checked the metadata fits in our storage
+        unsafe { out.0.as_mut_ptr().cast::<T::Metadata>().write(meta) };
+        out
+    }
+
+    /// Erase the `()` metadata of a pointer to a sized type. Unlike [`new`](Self::new), this is
+    /// usable in `const` contexts.
+    pub const fn sized() -> RawMeta {
+        RawMeta(MaybeUninit::zeroed())
+    }
+
+    /// Get back the metadata of a pointer to `T`
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as the metadata was created for
+    pub unsafe fn get<T: ?Sized + Pointee>(self) -> T::Metadata {
+        self.0.as_ptr().cast::<T::Metadata>().read()
+    }
+
+    /// Get the address stored in the raw bytes of this metadata, for comparisons
+    fn addr(self) -> usize {
+        // SAFETY: Storage is always fully initialized, as it starts zeroed
+        unsafe { self.0.assume_init() }.addr()
+    }
+
+    /// Get a pointer to the stored metadata
+    pub fn as_ptr(&self) -> NonNull<()> {
+        NonNull::from(&self.0).cast()
+    }
+}
+
+impl fmt::Debug for RawMeta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawMeta").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for RawMeta {
+    fn eq(&self, other: &Self) -> bool {
+        self.addr() == other.addr()
+    }
+}
+
+impl Eq for RawMeta {}
+
+impl PartialOrd for RawMeta {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RawMeta {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.addr().cmp(&other.addr())
+    }
+}
+
+impl Hash for RawMeta {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.addr().hash(state)
+    }
+}
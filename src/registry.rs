@@ -0,0 +1,89 @@
+//! A registry of trait object vtables, allowing erased values of known type to be reified as
+//! trait objects at runtime
+
+use alloc::collections::BTreeMap;
+use core::any::{Any, TypeId};
+use core::fmt;
+use core::marker::Unsize;
+use core::ptr::{self, DynMetadata, NonNull, Pointee};
+
+use crate::ErasedBox;
+
+/// A map from the [`TypeId`] of a concrete type to its vtable for the trait object `Dyn`.
+///
+/// This allows values stored as their concrete type, such as in an [`ErasedBox`] created with
+/// [`new_any`](ErasedBox::new_any), to be reified as a `Dyn` if their type was registered, via
+/// [`ErasedBox::reify_as`].
+pub struct VtableRegistry<Dyn: ?Sized + Pointee<Metadata = DynMetadata<Dyn>>> {
+    vtables: BTreeMap<TypeId, DynMetadata<Dyn>>,
+}
+
+impl<Dyn: ?Sized + Pointee<Metadata = DynMetadata<Dyn>>> VtableRegistry<Dyn> {
+    /// Create a new, empty `VtableRegistry`
+    pub fn new() -> VtableRegistry<Dyn> {
+        VtableRegistry {
+            vtables: BTreeMap::new(),
+        }
+    }
+
+    /// Register the vtable of `T` for `Dyn`. The vtable is retrieved by unsizing a pointer,
+    /// without needing a value of `T`.
+    pub fn register<T: Any + Unsize<Dyn>>(&mut self) {
+        let meta = ptr::metadata(NonNull::<T>::dangling().as_ptr() as *const Dyn);
+        self.vtables.insert(TypeId::of::<T>(), meta);
+    }
+
+    /// Get the vtable registered for the type with the provided [`TypeId`], if there is one
+    pub fn get(&self, type_id: TypeId) -> Option<DynMetadata<Dyn>> {
+        self.vtables.get(&type_id).copied()
+    }
+}
+
+impl<Dyn: ?Sized + Pointee<Metadata = DynMetadata<Dyn>>> Default for VtableRegistry<Dyn> {
+    fn default() -> Self {
+        VtableRegistry::new()
+    }
+}
+
+impl<Dyn: ?Sized + Pointee<Metadata = DynMetadata<Dyn>>> fmt::Debug for VtableRegistry<Dyn> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VtableRegistry")
+            .field("vtables", &self.vtables)
+            .finish()
+    }
+}
+
+impl ErasedBox {
+    /// Get a reference to the value stored in this `ErasedBox` as a `Dyn` trait object, using the
+    /// vtable registered for its type. Returns `None` if the box doesn't track its type, or the
+    /// type wasn't registered.
+    pub fn reify_as<'a, Dyn>(&'a self, registry: &VtableRegistry<Dyn>) -> Option<&'a Dyn>
+    where
+        Dyn: ?Sized + Pointee<Metadata = DynMetadata<Dyn>>,
+    {
+        let meta = registry.get(self.type_id()?)?;
+        let ptr = NonNull::<Dyn>::from_raw_parts(self.raw_ptr(), meta);
+        // SAFETY: The box stores a value of the tracked type, and the registry only stores
+        //         vtables matching the type they were registered for
+        Some(unsafe { ptr.as_ref() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    #[test]
+    fn test_reify_as() {
+        let mut registry = VtableRegistry::<dyn fmt::Debug>::new();
+        registry.register::<i32>();
+
+        let eb = ErasedBox::new_any(5i32);
+        let val = eb.reify_as(&registry).unwrap();
+        assert_eq!(format!("{:?}", val), "5");
+
+        assert!(ErasedBox::new_any(5u8).reify_as(&registry).is_none());
+        assert!(ErasedBox::new(5i32).reify_as(&registry).is_none());
+    }
+}
@@ -0,0 +1,99 @@
+//! Traits unifying the reify methods of the erased types, for code generic over them
+
+use alloc::boxed::Box;
+use core::ptr::Pointee;
+
+use crate::{ErasedBox, ErasedNonNull, ErasedRef, ThinErasedBox};
+
+/// An erased handle which may be reified as a reference to its value
+pub trait Reify {
+    /// Get a reference to the erased value
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally erased. For handles which don't own or
+    /// borrow their value, such as [`ErasedNonNull`], it must also be valid to dereference.
+    unsafe fn reify_ref<T: ?Sized + Pointee>(&self) -> &T;
+}
+
+/// An erased handle which owns its value, and may be reified as a [`Box`] of it
+pub trait ReifyOwned: Reify {
+    /// Convert this handle back into a [`Box`] of the erased value
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally erased
+    unsafe fn reify_box<T: ?Sized + Pointee>(self) -> Box<T>;
+}
+
+impl Reify for ErasedBox {
+    unsafe fn reify_ref<T: ?Sized + Pointee>(&self) -> &T {
+        ErasedBox::reify_ref(self)
+    }
+}
+
+impl ReifyOwned for ErasedBox {
+    unsafe fn reify_box<T: ?Sized + Pointee>(self) -> Box<T> {
+        ErasedBox::reify_box(self)
+    }
+}
+
+impl Reify for ThinErasedBox {
+    unsafe fn reify_ref<T: ?Sized + Pointee>(&self) -> &T {
+        self.reify_ptr_unbounded().as_ref()
+    }
+}
+
+impl ReifyOwned for ThinErasedBox {
+    unsafe fn reify_box<T: ?Sized + Pointee>(self) -> Box<T> {
+        self.reify_box_unbounded()
+    }
+}
+
+impl Reify for ErasedRef<'_> {
+    unsafe fn reify_ref<T: ?Sized + Pointee>(&self) -> &T {
+        ErasedRef::reify_ref(self)
+    }
+}
+
+impl Reify for ErasedNonNull {
+    unsafe fn reify_ref<T: ?Sized + Pointee>(&self) -> &T {
+        self.reify_ptr().as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    unsafe fn reify_str(handle: &impl Reify) -> &str {
+        handle.reify_ref::<str>()
+    }
+
+    unsafe fn unbox_str(handle: impl ReifyOwned) -> Box<str> {
+        handle.reify_box::<str>()
+    }
+
+    #[test]
+    fn test_reify_generic() {
+        let s: &str = "foo";
+        let eb = ErasedBox::from(Box::<str>::from(s));
+        let thin = ThinErasedBox::from(Box::<str>::from(s));
+
+        assert_eq!(unsafe { reify_str(&eb) }, "foo");
+        assert_eq!(unsafe { reify_str(&thin) }, "foo");
+        assert_eq!(unsafe { reify_str(&ErasedRef::new(s)) }, "foo");
+        assert_eq!(unsafe { reify_str(&ErasedNonNull::from(s)) }, "foo");
+
+        assert_eq!(&*unsafe { unbox_str(eb) }, "foo");
+        assert_eq!(&*unsafe { unbox_str(thin) }, "foo");
+    }
+
+    #[test]
+    fn test_reify_thin_sized() {
+        let thin = ThinErasedBox::new(String::from("bar"));
+        assert_eq!(unsafe { Reify::reify_ref::<String>(&thin) }, "bar");
+        assert_eq!(*unsafe { ReifyOwned::reify_box::<String>(thin) }, "bar");
+    }
+}
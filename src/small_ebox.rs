@@ -0,0 +1,171 @@
+//! An erased box which stores small values inline, only allocating for larger ones
+
+use core::mem::{self, MaybeUninit};
+use core::ptr::{NonNull, Pointee};
+use core::{fmt, ptr};
+
+use crate::thin_ebox::InnerData;
+use crate::ThinErasedBox;
+
+/// Inline storage for `N` bytes, aligned to a pointer
+#[repr(C)]
+struct Storage<const N: usize> {
+    _align: [usize; 0],
+    bytes: [MaybeUninit<u8>; N],
+}
+
+/// # Safety
+///
+/// The pointer must point to a valid `T`, which will not be used again
+unsafe fn drop_inline<T>(ptr: NonNull<()>) {
+    ptr::drop_in_place(ptr.cast::<T>().as_ptr());
+}
+
+enum Repr<const N: usize> {
+    Inline {
+        storage: Storage<N>,
+        drop: unsafe fn(NonNull<()>),
+    },
+    Heap(ThinErasedBox),
+}
+
+/// An erased box storing a sized value of unknown type, inline if it fits in `N` bytes and is at
+/// most pointer-aligned, or in a [`ThinErasedBox`] otherwise. This avoids allocating for small
+/// values, while still allowing any type to be stored.
+///
+/// Inline values are moved along with the box, so pointers to the value are only stable while
+/// the box isn't moved.
+pub struct SmallErasedBox<const N: usize> {
+    repr: Repr<N>,
+}
+
+impl<const N: usize> SmallErasedBox<N> {
+    /// Create a new `SmallErasedBox` from a value
+    pub fn new<T>(val: T) -> SmallErasedBox<N>
+    where
+        InnerData<T>: Pointee<Metadata = ()>,
+    {
+        if !Self::fits::<T>() {
+            return SmallErasedBox {
+                repr: Repr::Heap(ThinErasedBox::new(val)),
+            };
+        }
+
+        let mut storage = Storage {
+            _align: [],
+            bytes: [MaybeUninit::uninit(); N],
+        };
+        // SAFETY: We just checked the value fits in our storage, which is aligned enough for it
+        unsafe { storage.bytes.as_mut_ptr().cast::<T>().write(val) };
+
+        SmallErasedBox {
+            repr: Repr::Inline {
+                storage,
+                drop: drop_inline::<T>,
+            },
+        }
+    }
+
+    fn fits<T>() -> bool {
+        mem::size_of::<T>() <= N && mem::align_of::<T>() <= mem::align_of::<Storage<N>>()
+    }
+
+    /// Check whether the value in this `SmallErasedBox` is stored inline, rather than on the heap
+    pub fn is_inline(&self) -> bool {
+        matches!(self.repr, Repr::Inline { .. })
+    }
+
+    /// Get a reference to the value stored in this `SmallErasedBox`
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_ref<T>(&self) -> &T
+    where
+        InnerData<T>: Pointee<Metadata = ()>,
+    {
+        match &self.repr {
+            Repr::Inline { storage, .. } => &*storage.bytes.as_ptr().cast::<T>(),
+            Repr::Heap(eb) => eb.reify_ref(),
+        }
+    }
+
+    /// Get a mutable reference to the value stored in this `SmallErasedBox`
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_mut<T>(&mut self) -> &mut T
+    where
+        InnerData<T>: Pointee<Metadata = ()>,
+    {
+        match &mut self.repr {
+            Repr::Inline { storage, .. } => &mut *storage.bytes.as_mut_ptr().cast::<T>(),
+            Repr::Heap(eb) => eb.reify_mut(),
+        }
+    }
+}
+
+impl<const N: usize> fmt::Debug for SmallErasedBox<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SmallErasedBox")
+            .field("inline", &self.is_inline())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<const N: usize> Drop for SmallErasedBox<N> {
+    fn drop(&mut self) {
+        if let Repr::Inline { storage, drop } = &mut self.repr {
+            // SAFETY: The storage holds a value of the type the drop function was created for,
+            //         which is never used again
+            unsafe { drop(NonNull::from(&mut storage.bytes).cast()) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+    use alloc::string::String;
+
+    #[test]
+    fn test_inline() {
+        let mut eb = SmallErasedBox::<16>::new(5u8);
+        assert!(eb.is_inline());
+        *unsafe { eb.reify_mut::<u8>() } += 1;
+        assert_eq!(*unsafe { eb.reify_ref::<u8>() }, 6);
+
+        let eb = SmallErasedBox::<32>::new(String::from("foo"));
+        assert!(eb.is_inline());
+        assert_eq!(unsafe { eb.reify_ref::<String>() }, "foo");
+    }
+
+    #[test]
+    fn test_spilled() {
+        let eb = SmallErasedBox::<16>::new([7u8; 1024]);
+        assert!(!eb.is_inline());
+        assert_eq!(unsafe { eb.reify_ref::<[u8; 1024]>() }, &[7; 1024]);
+
+        #[repr(align(64))]
+        struct Aligned(u8);
+
+        let eb = SmallErasedBox::<128>::new(Aligned(1));
+        assert!(!eb.is_inline());
+        assert_eq!(unsafe { eb.reify_ref::<Aligned>() }.0, 1);
+    }
+
+    #[test]
+    fn test_drop() {
+        let rc = Rc::new(());
+        let inline = SmallErasedBox::<16>::new(rc.clone());
+        let spilled = SmallErasedBox::<0>::new(rc.clone());
+        assert!(inline.is_inline());
+        assert!(!spilled.is_inline());
+        assert_eq!(Rc::strong_count(&rc), 3);
+
+        drop((inline, spilled));
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
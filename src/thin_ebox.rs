@@ -1,10 +1,20 @@
 //! A more advanced erased box implementation, smaller but with a more complex implementation
 
-use alloc::alloc::Layout;
+use alloc::alloc::{handle_alloc_error, Layout};
 use alloc::boxed::Box;
+use core::alloc::AllocError;
+use core::future::Future;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::pin::Pin;
 use core::ptr::{NonNull, Pointee};
+use core::str::{self, Utf8Error};
+use core::sync::atomic;
+use core::task::{Context, Poll};
 use core::{fmt, mem, ptr};
 
+use crate::meta::ErasableMeta;
+
 // Ebox stuff
 
 mod hidden {
@@ -18,42 +28,112 @@ mod hidden {
         pub(super) data: T,
     }
 
+    impl<T: Pointee> InnerData<T> {
+        /// Create a new `InnerData` holding a value, which may be boxed and adopted by a
+        /// [`ThinErasedBox`] with [`ThinErasedBox::from_inner_box`]
+        pub fn from_value(val: T) -> InnerData<T> {
+            InnerData {
+                common: CommonInnerData::new::<T>(),
+                meta: ptr::metadata(&val),
+                data: val,
+            }
+        }
+    }
+
     impl<T: ?Sized + Pointee> InnerData<T> {
-        fn alloc(val: &T) -> NonNull<InnerData<T>>
+        /// Compute the layout of an `InnerData` holding `val`. The offset of each field is
+        /// computed the same way as `#[repr(C)]`, so the header is padded out to the alignment of
+        /// the data and the data is always correctly aligned, even for over-aligned types.
+        fn layout_for(val: &T) -> Layout {
+            let (header, _) = Layout::new::<CommonInnerData>()
+                .extend(Layout::new::<T::Metadata>())
+                .expect("Valid size/align pair");
+            let (layout, _) = header
+                .extend(Layout::for_value(val))
+                .expect("Valid size/align pair");
+            layout.pad_to_align()
+        }
+
+        fn try_alloc(val: &T) -> Option<NonNull<InnerData<T>>>
         where
             InnerData<T>: Pointee<Metadata = T::Metadata>,
         {
             let val_meta = (val as *const T).to_raw_parts().1;
 
-            let layout = {
-                let min_size = [
-                    mem::size_of::<CommonInnerData>(),
-                    mem::size_of::<T::Metadata>(),
-                    mem::size_of_val(val),
-                ]
-                .into_iter()
-                .sum();
-
-                let align = [
-                    mem::align_of::<CommonInnerData>(),
-                    mem::align_of::<T::Metadata>(),
-                    mem::align_of_val(val),
-                ]
-                .into_iter()
-                .max()
-                .unwrap();
-
-                Layout::from_size_align(min_size, align)
-                    .expect("Valid size/align pair")
-                    .pad_to_align()
-            };
+            let layout = Self::layout_for(val);
 
             // SAFETY: Layout size is guaranteed non-zero, as it's a sum involving at least one
             //         non-ZST
             let alloced = unsafe { alloc::alloc::alloc(layout) };
-            let new = NonNull::new(alloced).expect("Allocation returned nullptr");
+            let new = NonNull::new(alloced)?;
 
-            NonNull::from_raw_parts(new.cast(), val_meta)
+            Some(NonNull::from_raw_parts(new, val_meta))
+        }
+
+        fn alloc(val: &T) -> NonNull<InnerData<T>>
+        where
+            InnerData<T>: Pointee<Metadata = T::Metadata>,
+        {
+            Self::try_alloc(val).expect("Allocation returned nullptr")
+        }
+
+        /// Move a sized value into a new `InnerData`, returning the value back if the allocation
+        /// fails. Nothing is written to the allocation until it is known to have succeeded.
+        pub(crate) fn try_new_sized(val: T) -> Result<NonNull<InnerData<T>>, T>
+        where
+            T: Sized,
+            InnerData<T>: Pointee<Metadata = T::Metadata>,
+        {
+            let new_ptr = match Self::try_alloc(&val) {
+                Some(ptr) => ptr,
+                None => return Err(val),
+            };
+            let meta = (&val as *const T).to_raw_parts().1;
+
+            // SAFETY: We just allocated this pointer, we know it's valid
+            unsafe {
+                (*new_ptr.as_ptr()).common = CommonInnerData::new::<T>();
+            };
+            // SAFETY: We just allocated this pointer, we know it's valid
+            unsafe { (*new_ptr.as_ptr()).meta = meta };
+            // SAFETY: We just allocated this pointer, we know it's valid and aligned for `T`
+            unsafe { ptr::addr_of_mut!((*new_ptr.as_ptr()).data).write(val) };
+
+            Ok(new_ptr)
+        }
+
+        /// Allocate a new `InnerData` for a sized value, initializing the value in place with
+        /// the provided function
+        ///
+        /// # Safety
+        ///
+        /// `init` must fully initialize the value it is passed a pointer to
+        pub(crate) unsafe fn new_with(init: impl FnOnce(*mut T)) -> NonNull<InnerData<T>>
+        where
+            T: Sized,
+            InnerData<T>: Pointee<Metadata = T::Metadata>,
+        {
+            let layout = Layout::new::<InnerData<T>>();
+            // SAFETY: Layout size is guaranteed non-zero, as it includes the header
+            let new_ptr = NonNull::new(alloc::alloc::alloc(layout))
+                .unwrap_or_else(|| alloc::alloc::handle_alloc_error(layout))
+                .cast::<InnerData<T>>();
+
+            // SAFETY: We just allocated this pointer, we know it's valid
+            (*new_ptr.as_ptr()).common = CommonInnerData::new::<T>();
+            // SAFETY: We just allocated this pointer, we know it's valid and aligned for `T`
+            init(ptr::addr_of_mut!((*new_ptr.as_ptr()).data));
+
+            new_ptr
+        }
+
+        /// Move a sized value into a new `InnerData`, without an intermediate `Box`
+        pub(crate) fn new_sized(val: T) -> NonNull<InnerData<T>>
+        where
+            T: Sized,
+            InnerData<T>: Pointee<Metadata = T::Metadata>,
+        {
+            Self::try_new_sized(val).unwrap_or_else(|_| panic!("Allocation returned nullptr"))
         }
 
         pub(crate) fn new(val: Box<T>) -> NonNull<InnerData<T>>
@@ -106,14 +186,15 @@ mod hidden {
     }
 }
 
-use hidden::*;
+pub use hidden::InnerData;
 
 /// # Safety
 ///
 /// This function requires the input pointer be an erased pointer to an instance of `InnerData<T>`,
 /// and valid to pass to `Box::from_raw` (Derived from `Box::leak` or allocated with the global
-/// allocator and a correct layout).
-unsafe fn drop_impl<T>(ptr: NonNull<()>)
+/// allocator and a correct layout). If `drop_value` is false, the value must already have been
+/// moved out, and only the allocation is freed.
+unsafe fn drop_impl<T>(ptr: NonNull<()>, drop_value: bool)
 where
     T: ?Sized + Pointee,
     InnerData<T>: Pointee<Metadata = T::Metadata>,
@@ -129,13 +210,96 @@ where
     // SAFETY: We assume our input pointer is valid by safety constraints
     let meta = *meta_ptr;
     let ptr = NonNull::<InnerData<T>>::from_raw_parts(ptr, meta);
-    // SAFETY: We assume out input pointer is from `Box::into_raw` by safety constraints
-    Box::from_raw(ptr.as_ptr());
+    if drop_value {
+        // SAFETY: We assume out input pointer is from `Box::into_raw` by safety constraints
+        drop(Box::from_raw(ptr.as_ptr()));
+    } else {
+        // SAFETY: We assume our input pointer was allocated with the layout of an `InnerData<T>`
+        alloc::alloc::dealloc(ptr.as_ptr().cast(), Layout::for_value_raw(ptr.as_ptr()));
+    }
+}
+
+/// Like [`drop_impl`], but overwrites the value's bytes with zeros after dropping it and before
+/// deallocating. The bytes are also wiped if the value was moved out.
+///
+/// # Safety
+///
+/// The same requirements as [`drop_impl`] apply
+unsafe fn drop_zeroizing_impl<T>(ptr: NonNull<()>, drop_value: bool)
+where
+    T: ?Sized + Pointee,
+    InnerData<T>: Pointee<Metadata = T::Metadata>,
+{
+    let inner = inner_data::<T>(ptr);
+    // SAFETY: We assume our input pointer is valid by safety constraints. No references are
+    //         created, as the value may have been moved out.
+    let layout = Layout::for_value_raw(inner.as_ptr());
+    let data = ptr::addr_of_mut!((*inner.as_ptr()).data);
+    let size = mem::size_of_val_raw(data);
+
+    if drop_value {
+        ptr::drop_in_place(data);
+    }
+
+    // Volatile writes can't be elided, even though the memory is about to be freed
+    let bytes = data.cast::<u8>();
+    for i in 0..size {
+        ptr::write_volatile(bytes.add(i), 0);
+    }
+    atomic::compiler_fence(atomic::Ordering::SeqCst);
+
+    // SAFETY: We assume our input pointer was allocated with the layout of an `InnerData<T>`
+    alloc::alloc::dealloc(inner.as_ptr().cast(), layout);
+}
+
+/// Rebuild the full pointer to an `InnerData<T>` from an erased pointer to it, by reading the
+/// metadata stored in its header
+fn inner_data<T: ?Sized + Pointee>(inner: NonNull<()>) -> NonNull<InnerData<T>>
+where
+    InnerData<T>: Pointee<Metadata = T::Metadata>,
+{
+    // SAFETY: `inner` points to a valid `InnerData<T>`, which we know contains a `T::Metadata`
+    //         at an offset of 1 `CommonInnerData` from the start of the allocation, and that it
+    //         is part of the same allocation
+    let meta_ptr = unsafe {
+        inner
+            .as_ptr()
+            .cast::<CommonInnerData>()
+            .add(1)
+            .cast::<T::Metadata>()
+    };
+
+    // SAFETY: Our inner pointer is guaranteed valid and safe to dereference
+    let meta = unsafe { *meta_ptr };
+
+    NonNull::from_raw_parts(inner, meta)
+}
+
+/// Like [`inner_data`], but without requiring the metadata of `InnerData<T>` be known to match
+/// that of `T`, for generic code where the bound can't be written
+///
+/// # Safety
+///
+/// `inner` must point to a valid `InnerData<T>`
+unsafe fn inner_data_unbounded<T: ?Sized + Pointee>(inner: NonNull<()>) -> NonNull<InnerData<T>> {
+    // SAFETY: As in `inner_data`, the metadata is stored after a `CommonInnerData`
+    let meta = *inner
+        .as_ptr()
+        .cast::<CommonInnerData>()
+        .add(1)
+        .cast::<T::Metadata>();
+    // SAFETY: The metadata of a struct is that of its last field, so these are the same type
+    let meta = mem::transmute_copy::<T::Metadata, <InnerData<T> as Pointee>::Metadata>(&meta);
+
+    NonNull::from_raw_parts(inner, meta)
 }
 
 #[repr(C)]
 struct CommonInnerData {
-    drop: unsafe fn(NonNull<()>),
+    /// Frees the allocation, first dropping the value if the flag is set
+    drop: unsafe fn(NonNull<()>, bool),
+    #[cfg(feature = "type_name")]
+    type_name: &'static str,
 }
 
 impl CommonInnerData {
@@ -145,10 +309,18 @@ impl CommonInnerData {
     {
         CommonInnerData {
             drop: drop_impl::<T>,
+            #[cfg(feature = "type_name")]
+            type_name: core::any::type_name::<T>(),
         }
     }
 }
 
+/// The size in bytes of the header stored before the metadata and value in every
+/// [`ThinErasedBox`] allocation
+pub const fn thin_header_size() -> usize {
+    mem::size_of::<CommonInnerData>()
+}
+
 /// An erased box, storing a (possibly unsized) value of unknown type. Creating one is safe,
 /// but converting it back into any type is unsafe as it requires the user to know the type
 /// stored in the box.
@@ -156,39 +328,148 @@ impl CommonInnerData {
 /// This box will always be one pointer wide, storing the metadata on the heap alongside the
 /// contained data. This requires more unsafety, but less indirection. For a simpler alternative,
 /// take a look at [`ErasedBox`](crate::ErasedBox)
+///
+/// Boxes may store values containing further erased boxes, such as the nodes of a linked list or
+/// tree. Like with [`Box`], dropping such a structure drops each level recursively, so very deep
+/// chains may overflow the stack and should be unlinked iteratively instead.
 pub struct ThinErasedBox {
     /// Actually an [`InnerData`] of the type this box came from
-    inner: NonNull<()>,
+    pub(crate) inner: NonNull<()>,
 }
 
 impl ThinErasedBox {
     /// Create a new `ThinErasedBox` from a value
-    pub fn new<T: Pointee>(val: T) -> ThinErasedBox
+    pub fn new<T: Pointee<Metadata: ErasableMeta>>(val: T) -> ThinErasedBox
     where
         InnerData<T>: Pointee<Metadata = T::Metadata>,
     {
         Box::new(val).into()
     }
 
-    fn inner_data<T: ?Sized + Pointee>(&self) -> NonNull<InnerData<T>>
+    /// Create a new `ThinErasedBox` for a value of type `T`, which is initialized in place by the
+    /// provided function. This avoids constructing the value on the stack first, which matters for
+    /// large values. If `init` panics, the allocation is leaked.
+    ///
+    /// # Safety
+    ///
+    /// `init` must fully initialize the value it is passed a pointer to
+    pub unsafe fn new_with<T: Pointee<Metadata: ErasableMeta>>(
+        init: impl FnOnce(*mut T),
+    ) -> ThinErasedBox
     where
         InnerData<T>: Pointee<Metadata = T::Metadata>,
     {
-        // SAFETY: `inner` points to a valid `InnerData<T>`, which we know contains a `T::Metadata`
-        //         at an offset of 1 `CommonInnerData` from the start of the allocation, and that it
-        //         is part of the same allocation
-        let meta_ptr = unsafe {
-            self.inner
-                .as_ptr()
-                .cast::<CommonInnerData>()
-                .add(1)
-                .cast::<T::Metadata>()
+        ThinErasedBox {
+            inner: InnerData::new_with(init).cast(),
+        }
+    }
+
+    /// Create a new `ThinErasedBox` from a value, which will overwrite the bytes of the value with
+    /// zeros after dropping it, before freeing the allocation. This is useful for sensitive data
+    /// such as keys.
+    ///
+    /// Note that this only covers the boxed copy of the value. Reifying the box wipes the bytes
+    /// left behind when the value is moved out, but copies made before it was boxed, and the
+    /// reified value itself, are not affected.
+    pub fn new_zeroizing<T: Pointee<Metadata: ErasableMeta>>(val: T) -> ThinErasedBox
+    where
+        InnerData<T>: Pointee<Metadata = T::Metadata>,
+    {
+        let inner = InnerData::new_sized(val);
+        // SAFETY: We just allocated and initialized this pointer, we know it's valid
+        unsafe {
+            (*inner.as_ptr()).common.drop = drop_zeroizing_impl::<T>;
         };
+        ThinErasedBox {
+            inner: inner.cast(),
+        }
+    }
+
+    /// Create a new `ThinErasedBox` storing a pinned, boxed future. The box stores the pinned
+    /// pointer itself, so the future is never relocated.
+    pub fn from_boxed_future(f: Pin<Box<dyn Future<Output = ()>>>) -> ThinErasedBox {
+        ThinErasedBox::new(f)
+    }
+
+    /// Attempt to create a new `ThinErasedBox` from a value, returning the value and an
+    /// [`AllocError`] if the allocation fails instead of aborting.
+    pub fn try_new<T: Pointee<Metadata: ErasableMeta>>(
+        val: T,
+    ) -> Result<ThinErasedBox, (T, AllocError)>
+    where
+        InnerData<T>: Pointee<Metadata = T::Metadata>,
+    {
+        match InnerData::try_new_sized(val) {
+            Ok(inner) => Ok(ThinErasedBox {
+                inner: inner.cast(),
+            }),
+            Err(val) => Err((val, AllocError)),
+        }
+    }
+
+    /// Create a new `ThinErasedBox` adopting an existing boxed [`InnerData`], without copying the
+    /// value. The header of the `InnerData` is re-initialized for the type `T`.
+    pub fn from_inner_box<T: ?Sized + Pointee<Metadata: ErasableMeta>>(
+        b: Box<InnerData<T>>,
+    ) -> ThinErasedBox
+    where
+        InnerData<T>: Pointee<Metadata = T::Metadata>,
+    {
+        let inner = NonNull::from(Box::leak(b));
+        let meta = ptr::metadata(inner.as_ptr());
+
+        // SAFETY: The pointer came from `Box::leak`, we know it's valid
+        unsafe {
+            (*inner.as_ptr()).common = CommonInnerData::new::<T>();
+            (*inner.as_ptr()).meta = meta;
+        }
+        ThinErasedBox {
+            inner: inner.cast(),
+        }
+    }
 
-        // SAFETY: Our inner pointer is guaranteed valid and safe to dereference
-        let meta = unsafe { *meta_ptr };
+    fn inner_data<T: ?Sized + Pointee>(&self) -> NonNull<InnerData<T>>
+    where
+        InnerData<T>: Pointee<Metadata = T::Metadata>,
+    {
+        inner_data(self.inner)
+    }
 
-        NonNull::from_raw_parts(self.inner, meta)
+    /// Get the name of the type stored in this `ThinErasedBox`, for debugging purposes. This is
+    /// only recorded with the `type_name` feature enabled, and returns `None` otherwise.
+    pub fn type_name(&self) -> Option<&'static str> {
+        #[cfg(feature = "type_name")]
+        // SAFETY: Our inner pointer is guaranteed to start with a `CommonInnerData`
+        return Some(unsafe { self.inner.cast::<CommonInnerData>().as_ref() }.type_name);
+        #[cfg(not(feature = "type_name"))]
+        return None;
+    }
+
+    /// Check the invariants of this `ThinErasedBox` which can be verified without knowing the
+    /// stored type, as a diagnostic for memory corruption. Passing doesn't guarantee the box is
+    /// valid.
+    ///
+    /// # Panics
+    ///
+    /// If the allocation is misaligned for its header, or the stored drop function is null
+    pub fn assert_valid(&self) {
+        assert!(
+            self.inner.cast::<CommonInnerData>().is_aligned(),
+            "ThinErasedBox header is misaligned"
+        );
+        // SAFETY: Our inner pointer is guaranteed to start with a `CommonInnerData`, which starts
+        //         with a function pointer. It's read as a raw pointer, so a corrupted null value
+        //         can be detected.
+        let drop = unsafe { self.inner.cast::<*const ()>().read() };
+        assert!(!drop.is_null(), "ThinErasedBox drop function is null");
+    }
+
+    /// Get a one pointer wide erased reference to the value in this `ThinErasedBox`
+    pub fn as_thin_ref(&self) -> ThinErasedRef<'_> {
+        ThinErasedRef {
+            inner: self.inner,
+            _phantom: PhantomData,
+        }
     }
 
     /// Get a pointer to the value stored in this `ThinErasedBox`. This pointer is guaranteed
@@ -201,10 +482,43 @@ impl ThinErasedBox {
     where
         InnerData<T>: Pointee<Metadata = T::Metadata>,
     {
-        // SAFETY: `inner_data()` will return a valid pointer, assuming `T` matches our invariants
-        //         We don't hold these mutable references longer than this statement, they cannot
-        //         exist at the same time as another.
-        NonNull::from(&mut self.inner_data::<T>().as_mut().data)
+        let inner = self.inner_data::<T>();
+        // SAFETY: `inner_data()` will return a valid pointer, assuming `T` matches our invariants.
+        //         The field is projected without creating any intermediate references, so the
+        //         returned pointer keeps the full provenance of the allocation.
+        let ptr = NonNull::new_unchecked(ptr::addr_of_mut!((*inner.as_ptr()).data));
+        debug_assert_eq!(
+            ptr.as_ptr().cast::<u8>().addr() % mem::align_of_val_raw(ptr.as_ptr()),
+            0,
+            "ThinErasedBox data pointer is misaligned"
+        );
+        ptr
+    }
+
+    /// Like [`reify_ptr`](Self::reify_ptr), but without the bound on `InnerData<T>`, for generic
+    /// code where it can't be written
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub(crate) unsafe fn reify_ptr_unbounded<T: ?Sized + Pointee>(&self) -> NonNull<T> {
+        let inner = inner_data_unbounded::<T>(self.inner);
+        NonNull::new_unchecked(ptr::addr_of_mut!((*inner.as_ptr()).data))
+    }
+
+    /// Get a mutable pointer to the value stored in this `ThinErasedBox`. This pointer is
+    /// guaranteed correctly aligned and dereferencable, and valid to write through until this box
+    /// is dropped or otherwise accessed.
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_ptr_mut<T: ?Sized + Pointee>(&mut self) -> NonNull<T>
+    where
+        InnerData<T>: Pointee<Metadata = T::Metadata>,
+    {
+        // SAFETY: Matching safety invariants
+        self.reify_ptr()
     }
 
     /// Convert an `ThinErasedBox` back into a [`Box`] of the provided type
@@ -216,48 +530,112 @@ impl ThinErasedBox {
     where
         InnerData<T>: Pointee<Metadata = T::Metadata>,
     {
-        // Take ownership of inner, it will be dropped at the end of the function
-
         let inner = self.inner_data::<T>();
-        // SAFETY: `inner_data()` will return a valid pointer, assuming `T` matches our invariants
-        let inner_ref = inner.as_ref();
+        self.reify_box_from(inner)
+    }
 
+    /// Like [`reify_box`](Self::reify_box), but without the bound on `InnerData<T>`, for generic
+    /// code where it can't be written
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub(crate) unsafe fn reify_box_unbounded<T: ?Sized + Pointee>(self) -> Box<T> {
+        let inner = inner_data_unbounded::<T>(self.inner);
+        self.reify_box_from(inner)
+    }
+
+    /// # Safety
+    ///
+    /// `inner` must be the pointer to our `InnerData<T>`, for the type originally stored
+    unsafe fn reify_box_from<T: ?Sized + Pointee>(self, inner: NonNull<InnerData<T>>) -> Box<T> {
         // Allocate space to move the unsized value into
 
-        let layout = Layout::for_value(&inner_ref.data);
+        // SAFETY: `inner` is a valid pointer, assuming `T` matches our invariants
+        let layout = Layout::for_value(&inner.as_ref().data);
         let new_data = if layout.size() != 0 {
             // SAFETY: Layout is guaranteed not zero-sized, and correct for the value
             alloc::alloc::alloc(layout)
         } else {
             // A non-null aligned pointer to a zero-sized type
-            layout.align() as *mut u8
+            ptr::without_provenance_mut::<u8>(layout.align())
         };
+        let new_data = NonNull::new(new_data).unwrap_or_else(|| handle_alloc_error(layout));
+
+        // SAFETY: Our new pointer is from a fresh allocation for the layout of the value, or
+        //         a correctly aligned one if ZST
+        let out = self.reify_into_from(inner, new_data);
+        // SAFETY: The value was moved into an allocation valid for `Box::from_raw`
+        Box::from_raw(out.as_ptr())
+    }
+
+    /// Get the layout of the value stored in this `ThinErasedBox`, not including the header. This
+    /// is the layout of the buffer needed by [`reify_into`](Self::reify_into).
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn value_layout<T: ?Sized + Pointee>(&self) -> Layout
+    where
+        InnerData<T>: Pointee<Metadata = T::Metadata>,
+    {
+        Layout::for_value(self.reify_ref::<T>())
+    }
+
+    /// Move the value stored in this `ThinErasedBox` into a caller-provided buffer, and free the
+    /// box's allocation. Unlike [`reify_box`](Self::reify_box), this never allocates, so the
+    /// buffer may live anywhere, such as on the stack. Returns a pointer to the moved value in the
+    /// buffer, with its metadata. The caller is responsible for dropping it.
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box. `dst` must be valid
+    /// for writes of [`value_layout`](Self::value_layout) bytes, aligned to its alignment, and
+    /// must not overlap the box's allocation.
+    pub unsafe fn reify_into<T: ?Sized + Pointee>(self, dst: NonNull<u8>) -> NonNull<T>
+    where
+        InnerData<T>: Pointee<Metadata = T::Metadata>,
+    {
+        let inner = self.inner_data::<T>();
+        self.reify_into_from(inner, dst)
+    }
+
+    /// # Safety
+    ///
+    /// `inner` must be the pointer to our `InnerData<T>`, for the type originally stored. `dst`
+    /// must meet the requirements of [`reify_into`](Self::reify_into).
+    unsafe fn reify_into_from<T: ?Sized + Pointee>(
+        self,
+        inner: NonNull<InnerData<T>>,
+        dst: NonNull<u8>,
+    ) -> NonNull<T> {
+        // Take ownership of inner, it will be deallocated at the end of the function
+
+        // SAFETY: `inner` is a valid pointer, assuming `T` matches our invariants
+        let inner_ref = inner.as_ref();
+        let size = mem::size_of_val(&inner_ref.data);
 
         // Copy the unsized value out of inner
 
-        if layout.size() != 0 {
+        if size != 0 {
             // SAFETY:
             // - `inner_ref.data` is from a reference, so valid and aligned
-            // - Size isn't zero, `new_data` is from a fresh allocation, so valid and aligned
-            // - Pointers are from unrelated allocations, so cannot overlap
+            // - `dst` is valid and aligned for the value, by our invariants
+            // - The caller guarantees the pointers don't overlap
             ptr::copy_nonoverlapping(
                 (&inner_ref.data as *const T).cast::<u8>(),
-                new_data,
-                layout.size(),
+                dst.as_ptr(),
+                size,
             );
         }
+        let out = NonNull::from_raw_parts(dst, inner_ref.meta);
 
-        // Create the return box from the new allocation
-
-        // SAFETY: Our new pointer is guaranteed from a valid allocation for `Box::from_raw`, or
-        //         a correctly aligned one if ZST
-        let out = Box::from_raw(ptr::from_raw_parts_mut(new_data.cast(), inner_ref.meta));
-
-        // Deallocate inner without dropping, as we copied out the value
+        // Free inner without dropping, as we copied out the value. This goes through the stored
+        // drop function, so zeroizing boxes also wipe the bytes left behind
 
         // SAFETY: Our pointer came from `InnerData<T>::alloc`, which is of the correct type and
         //         layout, and guaranteed valid up until this point
-        alloc::alloc::dealloc(inner.as_ptr().cast(), Layout::for_value(inner_ref));
+        (inner_ref.common.drop)(inner.cast(), false);
 
         // Don't run our normal drop code on the inner we took ownership of
 
@@ -266,6 +644,44 @@ impl ThinErasedBox {
         out
     }
 
+    /// Get a reference to the slice stored in this `ThinErasedBox`, using the stored length. The
+    /// size of the value isn't stored, so unlike [`ErasedBox::reify_slice`](crate::ErasedBox::reify_slice)
+    /// this can't check the element type in debug builds.
+    ///
+    /// # Safety
+    ///
+    /// The box must store a `[T]`
+    pub unsafe fn reify_slice<T>(&self) -> &[T]
+    where
+        InnerData<[T]>: Pointee<Metadata = usize>,
+    {
+        self.reify_ref::<[T]>()
+    }
+
+    /// Get a reference to the sized value stored in this `ThinErasedBox`. Unlike
+    /// [`reify_ref`](Self::reify_ref), this never reads the stored metadata, as the offset of the
+    /// value is known at compile time.
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_sized_ref<T>(&self) -> &T {
+        let inner = self.inner.cast::<InnerData<T>>();
+        &*ptr::addr_of!((*inner.as_ptr()).data)
+    }
+
+    /// Copy the sized value stored in this `ThinErasedBox` out, leaving the box intact. Unlike
+    /// [`reify_box`](Self::reify_box), this neither consumes the box nor allocates.
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_copy<T: Copy>(&self) -> T {
+        let inner = self.inner.cast::<InnerData<T>>();
+        // SAFETY: The field is projected from the header type, so it is correctly aligned
+        ptr::addr_of!((*inner.as_ptr()).data).read()
+    }
+
     /// Get a reference to the value stored in this `ThinErasedBox`
     ///
     /// # Safety
@@ -282,6 +698,58 @@ impl ThinErasedBox {
         ptr.as_ref()
     }
 
+    /// Convert this `ThinErasedBox` back into a `Box<str>`
+    ///
+    /// # Safety
+    ///
+    /// The value originally stored in the box must be a `str`
+    pub unsafe fn reify_boxed_str(self) -> Box<str> {
+        self.reify_box::<str>()
+    }
+
+    /// Get a mutable reference to the closure stored in this `ThinErasedBox`
+    ///
+    /// # Safety
+    ///
+    /// The value originally stored in the box must be a `dyn FnMut()`
+    pub unsafe fn reify_fn_mut(&mut self) -> &mut dyn FnMut() {
+        self.reify_mut::<dyn FnMut()>()
+    }
+
+    /// Call the closure stored in this `ThinErasedBox`, consuming the box
+    ///
+    /// # Safety
+    ///
+    /// The value originally stored in the box must be a `dyn FnOnce()`
+    pub unsafe fn invoke_once(self) {
+        self.reify_box::<dyn FnOnce()>()()
+    }
+
+    /// Get a pinned reference to the future stored in this `ThinErasedBox`
+    ///
+    /// # Safety
+    ///
+    /// The box must have been created with [`from_boxed_future`](Self::from_boxed_future), or
+    /// otherwise store a `Pin<Box<dyn Future<Output = ()>>>`. A box storing a `dyn Future`
+    /// directly, such as one converted from a `Box<dyn Future<Output = ()>>`, must be polled with
+    /// [`poll_erased`] instead.
+    pub unsafe fn reify_future(self: Pin<&mut Self>) -> Pin<&mut dyn Future<Output = ()>> {
+        // The future lives in its own allocation, so moving the box doesn't move the future
+        let this = Pin::into_inner(self);
+        this.reify_mut::<Pin<Box<dyn Future<Output = ()>>>>()
+            .as_mut()
+    }
+
+    /// Get a reference to the bytes stored in this `ThinErasedBox` as a `str`, validating that
+    /// they are UTF-8 first.
+    ///
+    /// # Safety
+    ///
+    /// The value originally stored in the box must be a `[u8]` or `str`
+    pub unsafe fn reify_str_checked(&self) -> Result<&str, Utf8Error> {
+        str::from_utf8(self.reify_ref::<[u8]>())
+    }
+
     /// Get a mutable reference to the value stored in this `ThinErasedBox`
     ///
     /// # Safety
@@ -292,11 +760,144 @@ impl ThinErasedBox {
         InnerData<T>: Pointee<Metadata = T::Metadata>,
     {
         // SAFETY: Matching safety invariants
-        let mut ptr = self.reify_ptr();
+        let mut ptr = self.reify_ptr_mut();
         // SAFETY: Returned pointer is guaranteed valid, and we only access it with matching
         //         lifetimes to our own references
         ptr.as_mut()
     }
+
+    /// Get a mutable reference to the storage of the value in this `ThinErasedBox`, as possibly
+    /// uninitialized memory. This allows re-initializing the value after it has been manually
+    /// dropped.
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box. The value must be
+    /// initialized again before the box is dropped or reified as `T`.
+    pub unsafe fn reify_uninit_mut<T: Pointee>(&mut self) -> &mut MaybeUninit<T>
+    where
+        InnerData<T>: Pointee<Metadata = T::Metadata>,
+    {
+        // SAFETY: Matching safety invariants
+        let ptr = self.reify_ptr_mut::<T>();
+        // SAFETY: Returned pointer is guaranteed valid, and `MaybeUninit<T>` has the same layout
+        //         as `T`
+        ptr.cast::<MaybeUninit<T>>().as_mut()
+    }
+
+    /// Move the value out of this `ThinErasedBox`, along with a [`RefillGuard`] which must be used
+    /// to write a replacement back. This allows reusing the allocation for a new value, such as
+    /// for pooled boxes, without leaving it uninitialized.
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box. The guard must be
+    /// [refilled](RefillGuard::refill) before the box is used again. In debug builds, dropping the
+    /// guard without refilling it panics.
+    ///
+    /// Code between this call and the refill must not panic. Unwinding would drop the guard, which
+    /// aborts in debug builds, and leave the box to drop the moved-out value a second time.
+    pub unsafe fn reify_scope<T: Pointee>(&mut self) -> (T, RefillGuard<'_, T>)
+    where
+        InnerData<T>: Pointee<Metadata = T::Metadata>,
+    {
+        // SAFETY: Matching safety invariants
+        let slot = self.reify_uninit_mut::<T>();
+        // SAFETY: The value is initialized, and the guard ensures it's replaced before reuse
+        let val = slot.assume_init_read();
+        (val, RefillGuard { slot })
+    }
+}
+
+/// A guard for the empty slot of a [`ThinErasedBox`] whose value was moved out by
+/// [`reify_scope`](ThinErasedBox::reify_scope). A replacement must be written back with
+/// [`refill`](Self::refill) before the guard is dropped.
+#[must_use = "the box must be refilled before it's used again"]
+pub struct RefillGuard<'a, T> {
+    slot: &'a mut MaybeUninit<T>,
+}
+
+impl<'a, T> RefillGuard<'a, T> {
+    /// Write a new value into the box, returning a reference to it
+    pub fn refill(self, val: T) -> &'a mut T {
+        // SAFETY: The guard is forgotten below, so the slot is only reborrowed for `'a` once
+        let slot = unsafe { &mut *ptr::from_mut(self.slot) };
+        mem::forget(self);
+        slot.write(val)
+    }
+}
+
+impl<T> fmt::Debug for RefillGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RefillGuard").finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for RefillGuard<'_, T> {
+    fn drop(&mut self) {
+        // Refilling forgets the guard, so it's only dropped if it wasn't refilled
+        if cfg!(debug_assertions) {
+            panic!("ThinErasedBox was not refilled after reify_scope");
+        }
+    }
+}
+
+/// Poll the future stored in a [`ThinErasedBox`], which stores a `dyn Future` directly, such as
+/// one created from a `Box<dyn Future<Output = ()>>`. The future lives in the box's allocation,
+/// so moving the box doesn't move it.
+///
+/// # Safety
+///
+/// The value originally stored in the box must be a `dyn Future<Output = ()>`. Once polled, the
+/// future must not be moved out of the box, such as with
+/// [`reify_box`](ThinErasedBox::reify_box), as it is pinned. Boxes created with
+/// [`from_boxed_future`](ThinErasedBox::from_boxed_future) store a `Pin<Box<_>>` rather than the
+/// future itself, and must be polled through [`reify_future`](ThinErasedBox::reify_future)
+/// instead.
+pub unsafe fn poll_erased(b: Pin<&mut ThinErasedBox>, cx: &mut Context<'_>) -> Poll<()> {
+    let fut = Pin::into_inner(b).reify_mut::<dyn Future<Output = ()>>();
+    // SAFETY: The future is never moved out of its allocation, by safety constraints
+    Pin::new_unchecked(fut).poll(cx)
+}
+
+/// An erased reference to the value in a [`ThinErasedBox`], borrowing the box. Unlike
+/// [`ErasedRef`](crate::ErasedRef), this is always one pointer wide, as the metadata is read from
+/// the box's allocation. As it only borrows the box, it is also `Copy`.
+#[derive(Copy, Clone)]
+pub struct ThinErasedRef<'a> {
+    /// Actually an [`InnerData`] of the type the borrowed box came from
+    inner: NonNull<()>,
+    _phantom: PhantomData<&'a ()>,
+}
+
+impl<'a> ThinErasedRef<'a> {
+    /// Get back the reference to the value in the borrowed box
+    ///
+    /// # Safety
+    ///
+    /// The provided `T` must be the same type as originally stored in the box
+    pub unsafe fn reify_ref<T: ?Sized + Pointee>(self) -> &'a T
+    where
+        InnerData<T>: Pointee<Metadata = T::Metadata>,
+    {
+        let inner = inner_data::<T>(self.inner);
+        // SAFETY: The inner pointer is valid for `'a`, as we borrow the box for that long
+        &*ptr::addr_of!((*inner.as_ptr()).data)
+    }
+}
+
+impl fmt::Pointer for ThinErasedRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.inner, f)
+    }
+}
+
+impl fmt::Debug for ThinErasedRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThinErasedRef")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
 }
 
 impl fmt::Pointer for ThinErasedBox {
@@ -313,7 +914,7 @@ impl fmt::Debug for ThinErasedBox {
     }
 }
 
-impl<T: ?Sized + Pointee> From<Box<T>> for ThinErasedBox
+impl<T: ?Sized + Pointee<Metadata: ErasableMeta>> From<Box<T>> for ThinErasedBox
 where
     InnerData<T>: Pointee<Metadata = T::Metadata>,
 {
@@ -339,28 +940,54 @@ impl Drop for ThinErasedBox {
 
         // SAFETY: Our inner pointer came from `InnerData<T>::alloc`, which is of the correct type
         //         and layout to fulfill the `drop_impl` constraints
-        unsafe { f(self.inner) }
+        unsafe { f(self.inner, true) }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use alloc::string::String;
     use alloc::format;
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::cell::Cell;
+    use core::net::Ipv4Addr;
+    use core::ops::Range;
+    use core::task::{Context, Poll, Waker};
+    use core::time::Duration;
 
     #[test]
     fn test_eb_drop() {
         ThinErasedBox::new::<i32>(1);
     }
 
+    #[test]
+    fn test_eb_try_new() {
+        let eb = ThinErasedBox::try_new::<u64>(5).unwrap();
+        assert_eq!(*unsafe { eb.reify_ref::<u64>() }, 5);
+    }
+
     #[test]
     fn test_eb_reify_ptr() {
         let eb = ThinErasedBox::new::<u32>(1);
         let ptr1 = unsafe { eb.reify_ptr::<u32>() };
         let ptr2 = unsafe { eb.reify_ptr::<u32>() };
 
-        (|_, _| {})(ptr1, ptr2);
+        assert_eq!(ptr1, ptr2);
+    }
+
+    #[test]
+    fn test_eb_reify_ptr_mut() {
+        let mut eb = ThinErasedBox::new::<u32>(1);
+        let ptr = unsafe { eb.reify_ptr_mut::<u32>() }.as_ptr();
+        unsafe { *ptr = 2 };
+        unsafe { *ptr += 1 };
+        assert_eq!(*unsafe { eb.reify_ref::<u32>() }, 3);
+
+        let mut eb: ThinErasedBox = (Box::new([1, 2, 3]) as Box<[i32]>).into();
+        let ptr = unsafe { eb.reify_ptr_mut::<[i32]>() }.as_ptr();
+        unsafe { (*ptr)[1] = 5 };
+        assert_eq!(unsafe { eb.reify_ref::<[i32]>() }, [1, 5, 3]);
     }
 
     #[test]
@@ -372,7 +999,7 @@ mod tests {
     fn test_eb_reify_ref() {
         let eb = ThinErasedBox::new::<bool>(true);
         let val = unsafe { eb.reify_ref::<bool>() };
-        assert_eq!(*val, true);
+        assert!(*val);
     }
 
     #[test]
@@ -385,6 +1012,96 @@ mod tests {
         assert_eq!(*val2, 2.5);
     }
 
+    #[test]
+    fn test_eb_reify_uninit_mut() {
+        let mut eb = ThinErasedBox::new(String::from("foo"));
+        unsafe { ptr::drop_in_place(eb.reify_mut::<String>()) };
+        unsafe { eb.reify_uninit_mut::<String>() }.write(String::from("bar"));
+        assert_eq!(unsafe { eb.reify_ref::<String>() }, "bar");
+    }
+
+    #[test]
+    fn test_reify_slice() {
+        let eb = ThinErasedBox::from(Box::<[u32]>::from(&[1, 2, 3][..]));
+        assert_eq!(unsafe { eb.reify_slice::<u32>() }, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_reify_into() {
+        let eb = ThinErasedBox::from(Box::<[u8]>::from(&[1, 2, 3, 4][..]));
+        let layout = unsafe { eb.value_layout::<[u8]>() };
+        assert_eq!(layout, Layout::new::<[u8; 4]>());
+
+        let mut buf = MaybeUninit::<[u8; 4]>::uninit();
+        let out = unsafe { eb.reify_into::<[u8]>(NonNull::from(&mut buf).cast()) };
+        assert_eq!(unsafe { out.as_ref() }, [1, 2, 3, 4]);
+        assert_eq!(unsafe { buf.assume_init() }, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_reify_into_drop() {
+        let eb = ThinErasedBox::new(String::from("foo"));
+        let mut buf = MaybeUninit::<String>::uninit();
+        let out = unsafe { eb.reify_into::<String>(NonNull::from(&mut buf).cast()) };
+        assert_eq!(out, NonNull::from(&mut buf).cast());
+        assert_eq!(unsafe { buf.assume_init() }, "foo");
+    }
+
+    #[test]
+    fn test_reify_scope() {
+        let mut eb = ThinErasedBox::new(String::from("foo"));
+        let (mut val, guard) = unsafe { eb.reify_scope::<String>() };
+        val.push_str("bar");
+        guard.refill(val).push('!');
+        assert_eq!(unsafe { eb.reify_ref::<String>() }, "foobar!");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "ThinErasedBox was not refilled after reify_scope"]
+    fn test_reify_scope_forgotten() {
+        let mut eb = ThinErasedBox::new(1u32);
+        let (val, guard) = unsafe { eb.reify_scope::<u32>() };
+        assert_eq!(val, 1);
+        drop(guard);
+    }
+
+    #[test]
+    fn test_std_types() {
+        let dur = Duration::new(5, 123_456_789);
+        let eb = ThinErasedBox::new(dur);
+        assert_eq!(*unsafe { eb.reify_ref::<Duration>() }, dur);
+        assert_eq!(*unsafe { eb.reify_box::<Duration>() }, dur);
+
+        let addr = Ipv4Addr::new(127, 0, 0, 1);
+        let eb = ThinErasedBox::new(addr);
+        assert_eq!(*unsafe { eb.reify_ref::<Ipv4Addr>() }, addr);
+        assert_eq!(*unsafe { eb.reify_box::<Ipv4Addr>() }, addr);
+
+        let eb = ThinErasedBox::new::<Range<usize>>(3..10);
+        assert_eq!(*unsafe { eb.reify_ref::<Range<usize>>() }, 3..10);
+        assert_eq!(*unsafe { eb.reify_box::<Range<usize>>() }, 3..10);
+
+        let tuple: (u8, u16, u32) = (u8::MAX, 0xABCD, 0xDEAD_BEEF);
+        let eb = ThinErasedBox::new(tuple);
+        assert_eq!(*unsafe { eb.reify_ref::<(u8, u16, u32)>() }, tuple);
+        assert_eq!(*unsafe { eb.reify_box::<(u8, u16, u32)>() }, tuple);
+    }
+
+    #[test]
+    fn test_thin_ref() {
+        let eb = ThinErasedBox::new(String::from("foo"));
+        let r1 = eb.as_thin_ref();
+        let r2 = r1;
+
+        assert_eq!(unsafe { r1.reify_ref::<String>() }, "foo");
+        assert_eq!(unsafe { r2.reify_ref::<String>() }, "foo");
+        assert_eq!(mem::size_of::<ThinErasedRef<'_>>(), mem::size_of::<usize>());
+
+        let eb: ThinErasedBox = (Box::new([1, 2, 3]) as Box<[i32]>).into();
+        assert_eq!(unsafe { eb.as_thin_ref().reify_ref::<[i32]>() }, [1, 2, 3]);
+    }
+
     #[test]
     fn test_zst() {
         #[derive(Debug, PartialEq)]
@@ -394,16 +1111,149 @@ mod tests {
         assert_eq!(*unsafe { eb.reify_ref::<Foo>() }, Foo);
     }
 
+    #[test]
+    fn test_over_aligned() {
+        #[derive(Debug, PartialEq)]
+        #[repr(align(64))]
+        struct Align64(u8);
+
+        #[derive(Debug, PartialEq)]
+        #[repr(align(128))]
+        struct Align128([u32; 3]);
+
+        let eb = ThinErasedBox::new(Align64(7));
+        let ptr = unsafe { eb.reify_ptr::<Align64>() };
+        assert_eq!(ptr.as_ptr().addr() % 64, 0);
+        assert_eq!(*unsafe { eb.reify_ref::<Align64>() }, Align64(7));
+
+        let eb = ThinErasedBox::new(Align128([1, 2, 3]));
+        let ptr = unsafe { eb.reify_ptr::<Align128>() };
+        assert_eq!(ptr.as_ptr().addr() % 128, 0);
+        assert_eq!(*unsafe { eb.reify_ref::<Align128>() }, Align128([1, 2, 3]));
+
+        let eb: ThinErasedBox = (Box::new([Align64(1), Align64(2)]) as Box<[Align64]>).into();
+        let ptr = unsafe { eb.reify_ptr::<[Align64]>() };
+        assert_eq!(ptr.as_ptr().cast::<u8>().addr() % 64, 0);
+        assert_eq!(
+            unsafe { eb.reify_ref::<[Align64]>() },
+            [Align64(1), Align64(2)]
+        );
+    }
+
     #[test]
     fn test_str() {
         let eb: ThinErasedBox = String::from("foo").into_boxed_str().into();
         assert_eq!(unsafe { eb.reify_ref::<str>() }, "foo");
     }
 
+    #[test]
+    fn test_str_box() {
+        let eb: ThinErasedBox = String::from("foo").into_boxed_str().into();
+        let b = unsafe { eb.reify_box::<str>() };
+        assert_eq!(&*b, "foo");
+
+        let eb: ThinErasedBox = String::from("bar").into_boxed_str().into();
+        let b = unsafe { eb.reify_boxed_str() };
+        assert_eq!(&*b, "bar");
+    }
+
+    #[test]
+    fn test_str_checked() {
+        let eb: ThinErasedBox = (Box::new(*b"foo") as Box<[u8]>).into();
+        assert_eq!(unsafe { eb.reify_str_checked() }, Ok("foo"));
+
+        let eb: ThinErasedBox = (Box::new([0xC3u8, 0x28]) as Box<[u8]>).into();
+        assert!(unsafe { eb.reify_str_checked() }.is_err());
+    }
+
+    #[test]
+    fn test_fn_mut() {
+        let mut count = 0;
+        let mut eb: ThinErasedBox = (Box::new(|| count += 1) as Box<dyn FnMut()>).into();
+        unsafe { eb.reify_fn_mut()() };
+        unsafe { eb.reify_fn_mut()() };
+        drop(eb);
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_fn_once() {
+        let s = String::from("foo");
+        let mut out = None;
+        let eb: ThinErasedBox = (Box::new(|| out = Some(s)) as Box<dyn FnOnce() + '_>).into();
+        unsafe { eb.invoke_once() };
+        assert_eq!(out.as_deref(), Some("foo"));
+    }
+
+    struct CountFuture {
+        count: u8,
+        addr: Option<usize>,
+    }
+
+    impl Future for CountFuture {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            // The future must be at the same address for every poll
+            let addr = (&*self as *const Self).addr();
+            assert_eq!(*self.addr.get_or_insert(addr), addr);
+
+            self.count += 1;
+            if self.count == 3 {
+                Poll::Ready(())
+            } else {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    #[test]
+    fn test_future() {
+        let fut = Box::pin(CountFuture {
+            count: 0,
+            addr: None,
+        });
+        let mut eb = ThinErasedBox::from_boxed_future(fut);
+        let mut cx = Context::from_waker(Waker::noop());
+
+        let mut polls = 0;
+        loop {
+            polls += 1;
+            let fut = unsafe { Pin::new(&mut eb).reify_future() };
+            if fut.poll(&mut cx).is_ready() {
+                break;
+            }
+            // Moving the box must not move the future
+            eb = core::convert::identity(eb);
+        }
+        assert_eq!(polls, 3);
+    }
+
+    #[test]
+    fn test_poll_erased() {
+        let fut = Box::new(CountFuture {
+            count: 0,
+            addr: None,
+        });
+        let mut eb = ThinErasedBox::from(fut as Box<dyn Future<Output = ()>>);
+        let mut cx = Context::from_waker(Waker::noop());
+
+        let mut polls = 0;
+        while unsafe { poll_erased(Pin::new(&mut eb), &mut cx) }.is_pending() {
+            polls += 1;
+            eb = core::convert::identity(eb);
+        }
+        assert_eq!(polls, 2);
+    }
+
     #[test]
     fn test_dyn_val() {
         let eb: ThinErasedBox = (Box::new(123.45) as Box<dyn fmt::Debug>).into();
-        assert_eq!(format!("{:?}", unsafe { eb.reify_ref::<dyn fmt::Debug>() }), "123.45");
+        assert_eq!(
+            format!("{:?}", unsafe { eb.reify_ref::<dyn fmt::Debug>() }),
+            "123.45"
+        );
     }
 
     #[test]
@@ -411,4 +1261,137 @@ mod tests {
         let eb: ThinErasedBox = (Box::new([1, 2, 3]) as Box<[i32]>).into();
         assert_eq!(unsafe { eb.reify_ref::<[i32]>() }, [1, 2, 3]);
     }
+
+    #[test]
+    fn test_recursive() {
+        struct Node<'a> {
+            value: i32,
+            next: Option<ThinErasedBox>,
+            drops: &'a Cell<u32>,
+        }
+
+        impl Drop for Node<'_> {
+            fn drop(&mut self) {
+                self.drops.set(self.drops.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let head = (1..=3).rev().fold(None, |next, value| {
+            Some(ThinErasedBox::new(Node {
+                value,
+                next,
+                drops: &drops,
+            }))
+        });
+
+        let mut values = Vec::new();
+        let mut cur = head.as_ref();
+        while let Some(eb) = cur {
+            let node = unsafe { eb.reify_ref::<Node<'_>>() };
+            values.push(node.value);
+            cur = node.next.as_ref();
+        }
+        assert_eq!(values, [1, 2, 3]);
+
+        drop(head);
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn test_from_inner_box() {
+        fn make_inner(val: String) -> Box<InnerData<String>> {
+            Box::new(InnerData::from_value(val))
+        }
+
+        let eb = ThinErasedBox::from_inner_box(make_inner(String::from("foo")));
+        assert_eq!(unsafe { eb.reify_ref::<String>() }, "foo");
+    }
+
+    #[test]
+    fn test_reify_sized_ref() {
+        let eb = ThinErasedBox::new(Range {
+            start: 1u16,
+            end: 5,
+        });
+        assert_eq!(unsafe { eb.reify_sized_ref::<Range<u16>>() }, &(1..5));
+
+        #[repr(align(32))]
+        struct Align32(u8);
+
+        let eb = ThinErasedBox::new(Align32(3));
+        let val = unsafe { eb.reify_sized_ref::<Align32>() };
+        assert_eq!(
+            ptr::from_ref(val),
+            unsafe { eb.reify_ptr::<Align32>() }.as_ptr()
+        );
+        assert_eq!(val.0, 3);
+    }
+
+    #[test]
+    fn test_new_with() {
+        let eb = unsafe {
+            ThinErasedBox::new_with::<[u8; 4096]>(|ptr| {
+                let bytes = ptr.cast::<u8>();
+                for i in 0..4096 {
+                    bytes.add(i).write(i as u8);
+                }
+            })
+        };
+        let arr = unsafe { eb.reify_ref::<[u8; 4096]>() };
+        assert_eq!(arr[0], 0);
+        assert_eq!(arr[4095], 255);
+        assert!(arr.iter().enumerate().all(|(i, &b)| b == i as u8));
+    }
+
+    #[test]
+    fn test_reify_copy() {
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        #[repr(align(16))]
+        struct Point {
+            x: u8,
+            y: u64,
+        }
+
+        let mut eb = ThinErasedBox::new(Point { x: 1, y: 2 });
+        assert_eq!(unsafe { eb.reify_copy::<Point>() }, Point { x: 1, y: 2 });
+
+        unsafe { eb.reify_mut::<Point>() }.x = 3;
+        assert_eq!(unsafe { eb.reify_copy::<Point>() }, Point { x: 3, y: 2 });
+    }
+
+    #[test]
+    fn test_type_name() {
+        let eb = ThinErasedBox::new(Vec::<u8>::new());
+        if cfg!(feature = "type_name") {
+            assert_eq!(eb.type_name(), Some(core::any::type_name::<Vec<u8>>()));
+        } else {
+            assert_eq!(eb.type_name(), None);
+        }
+    }
+
+    #[test]
+    fn test_thin_header_size() {
+        const SIZE: usize = thin_header_size();
+        if cfg!(feature = "type_name") {
+            assert_eq!(SIZE, mem::size_of::<fn()>() + mem::size_of::<&str>());
+        } else {
+            assert_eq!(SIZE, mem::size_of::<fn()>());
+        }
+    }
+
+    #[test]
+    fn test_assert_valid() {
+        ThinErasedBox::new(5u32).assert_valid();
+        ThinErasedBox::from(Box::new([1u8, 2]) as Box<[u8]>).assert_valid();
+    }
+
+    #[test]
+    #[should_panic = "ThinErasedBox drop function is null"]
+    fn test_assert_valid_null_drop() {
+        // Never dropped, as the corrupted header can't be used to free it
+        let eb = mem::ManuallyDrop::new(ThinErasedBox::new(5u32));
+        unsafe { eb.inner.cast::<*const ()>().write(ptr::null()) };
+        eb.assert_valid();
+    }
 }
@@ -0,0 +1,134 @@
+//! An erased box checked at compile time by a key type, allowing safe reification
+
+use alloc::boxed::Box;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ptr::Pointee;
+
+use crate::meta::ErasableMeta;
+use crate::ErasedBox;
+
+/// A witness type, declaring which type of value is stored in a [`TypedErasedBox`] erased with
+/// it. Usually implemented for zero-sized types with [`erased_key!`](crate::erased_key).
+pub trait ErasedKey {
+    /// The type of the value stored in boxes using this key
+    type Value: ?Sized + Pointee<Metadata: ErasableMeta>;
+}
+
+/// Declare a zero-sized key type for use with [`TypedErasedBox`], witnessing the provided value
+/// type.
+///
+/// ```
+/// # use craft_eraser::erased_key;
+/// erased_key!(pub NameKey => String);
+/// ```
+#[macro_export]
+macro_rules! erased_key {
+    ($vis:vis $name:ident => $ty:ty) => {
+        #[derive(Copy, Clone, Debug)]
+        $vis struct $name;
+
+        impl $crate::typed_ebox::ErasedKey for $name {
+            type Value = $ty;
+        }
+    };
+}
+
+/// An erased box, tagged with a key type `K` that witnesses the type of the stored value.
+/// Reification requires a value of the same key, so the compiler enforces that the erasing and
+/// reifying sites agree on the type, and reification is safe.
+///
+/// Mixing keys fails to compile:
+///
+/// ```compile_fail
+/// # use craft_eraser::{erased_key, TypedErasedBox};
+/// erased_key!(IntKey => i32);
+/// erased_key!(FloatKey => f32);
+///
+/// let eb = TypedErasedBox::<IntKey>::new(1);
+/// let val = eb.reify_ref(&FloatKey);
+/// ```
+pub struct TypedErasedBox<K: ErasedKey> {
+    inner: ErasedBox,
+    _key: PhantomData<fn() -> K>,
+}
+
+impl<K: ErasedKey> TypedErasedBox<K> {
+    /// Create a new `TypedErasedBox` from a value
+    pub fn new(val: K::Value) -> TypedErasedBox<K>
+    where
+        K::Value: Sized,
+    {
+        TypedErasedBox::from_box(Box::new(val))
+    }
+
+    /// Create a new `TypedErasedBox` from an existing `Box`
+    pub fn from_box(val: Box<K::Value>) -> TypedErasedBox<K> {
+        TypedErasedBox {
+            inner: ErasedBox::from(val),
+            _key: PhantomData,
+        }
+    }
+
+    /// Get a reference to the value stored in this `TypedErasedBox`
+    pub fn reify_ref(&self, _key: &K) -> &K::Value {
+        // SAFETY: The key type guarantees the stored type
+        unsafe { self.inner.reify_ref() }
+    }
+
+    /// Get a mutable reference to the value stored in this `TypedErasedBox`
+    pub fn reify_mut(&mut self, _key: &K) -> &mut K::Value {
+        // SAFETY: The key type guarantees the stored type
+        unsafe { self.inner.reify_mut() }
+    }
+
+    /// Convert this `TypedErasedBox` back into a [`Box`] of the stored value
+    pub fn reify_box(self, _key: &K) -> Box<K::Value> {
+        // SAFETY: The key type guarantees the stored type
+        unsafe { self.inner.reify_box() }
+    }
+
+    /// Discard the key of this box, converting it into an unchecked [`ErasedBox`]
+    pub fn into_erased(self) -> ErasedBox {
+        self.inner
+    }
+}
+
+/// Create a new [`TypedErasedBox`] storing a value of type `T`, witnessed by the key `K`
+pub fn erase_typed<T, K: ErasedKey<Value = T>>(val: T) -> TypedErasedBox<K> {
+    TypedErasedBox::new(val)
+}
+
+impl<K: ErasedKey> fmt::Debug for TypedErasedBox<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypedErasedBox")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    erased_key!(NameKey => String);
+    erased_key!(SliceKey => [u8]);
+
+    #[test]
+    fn test_typed() {
+        let mut eb = erase_typed::<_, NameKey>(String::from("foo"));
+        assert_eq!(eb.reify_ref(&NameKey), "foo");
+        eb.reify_mut(&NameKey).push_str("bar");
+        assert_eq!(*eb.reify_box(&NameKey), "foobar");
+    }
+
+    #[test]
+    fn test_typed_unsized() {
+        let eb = TypedErasedBox::<SliceKey>::from_box(Box::new([1, 2, 3]));
+        assert_eq!(eb.reify_ref(&SliceKey), [1, 2, 3]);
+
+        let eb = eb.into_erased();
+        assert_eq!(unsafe { eb.reify_ref::<[u8]>() }, [1, 2, 3]);
+    }
+}
@@ -0,0 +1,134 @@
+//! Tests which rely on controlling the global allocator, such as allocation failure
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::ptr::{self, NonNull};
+
+use craft_eraser::{ErasedBox, ThinErasedBox};
+
+struct TestAlloc;
+
+thread_local! {
+    /// Number of allocations allowed to succeed on this thread, or `None` for unlimited
+    static BUDGET: Cell<Option<usize>> = const { Cell::new(None) };
+    /// Address and length of a region to inspect when the allocation containing it is freed
+    static WATCH: Cell<Option<(usize, usize)>> = const { Cell::new(None) };
+    /// Whether the watched region was all zeros when it was freed
+    static ZEROED: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+unsafe impl GlobalAlloc for TestAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allowed = BUDGET.with(|b| match b.get() {
+            Some(0) => false,
+            Some(n) => {
+                b.set(Some(n - 1));
+                true
+            }
+            None => true,
+        });
+
+        if allowed {
+            System.alloc(layout)
+        } else {
+            ptr::null_mut()
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if let Some((addr, len)) = WATCH.get() {
+            if ptr.addr() <= addr && addr + len <= ptr.addr() + layout.size() {
+                let region = std::slice::from_raw_parts(ptr.add(addr - ptr.addr()), len);
+                ZEROED.set(Some(region.iter().all(|&b| b == 0)));
+                WATCH.set(None);
+            }
+        }
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: TestAlloc = TestAlloc;
+
+/// Run a function with only `n` allocations allowed to succeed
+fn with_budget<R>(n: usize, f: impl FnOnce() -> R) -> R {
+    BUDGET.with(|b| b.set(Some(n)));
+    let out = f();
+    BUDGET.with(|b| b.set(None));
+    out
+}
+
+/// Free a thin box with the provided function, returning whether the bytes of its `T` were zeroed
+/// when its allocation was freed
+fn zeroed_on_free<T>(eb: ThinErasedBox, free: impl FnOnce(ThinErasedBox)) -> Option<bool> {
+    let data = unsafe { eb.reify_ptr::<T>() };
+    WATCH.set(Some((data.as_ptr().addr(), std::mem::size_of::<T>())));
+    ZEROED.set(None);
+    free(eb);
+    ZEROED.get()
+}
+
+#[test]
+fn test_thin_zeroizing() {
+    let eb = ThinErasedBox::new_zeroizing([0xABu8; 32]);
+    assert_eq!(zeroed_on_free::<[u8; 32]>(eb, drop), Some(true));
+
+    let eb = ThinErasedBox::new([0xABu8; 32]);
+    assert_eq!(zeroed_on_free::<[u8; 32]>(eb, drop), Some(false));
+}
+
+#[test]
+fn test_thin_zeroizing_reify() {
+    let eb = ThinErasedBox::new_zeroizing([0xABu8; 32]);
+    let zeroed = zeroed_on_free::<[u8; 32]>(eb, |eb| {
+        let val = unsafe { eb.reify_box::<[u8; 32]>() };
+        assert_eq!(*val, [0xAB; 32]);
+    });
+    assert_eq!(zeroed, Some(true));
+
+    let eb = ThinErasedBox::new_zeroizing(String::from("secret"));
+    let zeroed = zeroed_on_free::<String>(eb, |eb| {
+        let mut val = std::mem::MaybeUninit::<String>::uninit();
+        let out = unsafe { eb.reify_into::<String>(NonNull::from(&mut val).cast()) };
+        assert_eq!(unsafe { out.as_ref() }, "secret");
+        drop(unsafe { val.assume_init() });
+    });
+    assert_eq!(zeroed, Some(true));
+}
+
+#[test]
+fn test_thin_try_new_fail() {
+    let val = [1u64, 2, 3, 4];
+
+    let res = with_budget(0, || ThinErasedBox::try_new(val));
+    let (val, _) = res.unwrap_err();
+    assert_eq!(val, [1, 2, 3, 4]);
+}
+
+#[test]
+fn test_thin_try_new_success() {
+    let val = String::from("foo");
+
+    let res = with_budget(1, || ThinErasedBox::try_new(val));
+    let eb = res.unwrap();
+    assert_eq!(unsafe { eb.reify_ref::<String>() }, "foo");
+}
+
+#[test]
+fn test_try_new_fail() {
+    let val = String::from("foo");
+
+    let res = with_budget(0, || ErasedBox::try_new(val));
+    let (val, _) = res.unwrap_err();
+    assert_eq!(val, "foo");
+}
+
+#[test]
+fn test_try_new_single_alloc() {
+    // Metadata is stored inline, so only the value allocation should be required
+    let val = String::from("foo");
+
+    let res = with_budget(1, || ErasedBox::try_new(val));
+    let eb = res.unwrap();
+    assert_eq!(unsafe { eb.reify_ref::<String>() }, "foo");
+}
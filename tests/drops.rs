@@ -0,0 +1,75 @@
+//! Erases owning types with multi-level drops, checking every nested allocation is freed exactly
+//! once. Leaks and double frees are caught when run under Miri:
+//!
+//! ```text
+//! cargo +nightly miri test --test drops
+//! ```
+
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use craft_eraser::{ErasedBox, SmallErasedBox, ThinErasedBox};
+
+fn strings() -> Vec<String> {
+    (0..8).map(|i| format!("string {}", i)).collect()
+}
+
+fn map() -> BTreeMap<i32, String> {
+    (0..32).map(|i| (i, format!("value {}", i))).collect()
+}
+
+#[test]
+fn test_drop_vec() {
+    drop(ErasedBox::new(strings()));
+    drop(ThinErasedBox::new(strings()));
+    drop(SmallErasedBox::<2>::new(strings()));
+    drop(ErasedBox::from_vec(strings()));
+}
+
+#[test]
+fn test_drop_btree_map() {
+    drop(ErasedBox::new(map()));
+    drop(ThinErasedBox::new(map()));
+    drop(SmallErasedBox::<4>::new(map()));
+}
+
+#[test]
+fn test_drop_boxed_slice() {
+    drop(ErasedBox::from(strings().into_boxed_slice()));
+    drop(ThinErasedBox::from(strings().into_boxed_slice()));
+    drop(ErasedBox::new(strings().into_boxed_slice()));
+}
+
+#[test]
+fn test_reify_then_drop() {
+    let eb = ErasedBox::new(map());
+    assert_eq!(
+        unsafe { eb.reify_ref::<BTreeMap<i32, String>>() }[&3],
+        "value 3"
+    );
+    let map = unsafe { eb.reify_box::<BTreeMap<i32, String>>() };
+    assert_eq!(map.len(), 32);
+
+    let eb = ThinErasedBox::from(strings().into_boxed_slice());
+    let slice = unsafe { eb.reify_box::<[String]>() };
+    assert_eq!(slice[7], "string 7");
+}
+
+#[test]
+fn test_drop_once() {
+    let rc = Rc::new(String::from("shared"));
+    let nested = || {
+        let vec = vec![rc.clone(); 4];
+        let map = (0..4).map(|i| (i, vec.clone())).collect::<BTreeMap<_, _>>();
+        (vec.into_boxed_slice(), map)
+    };
+
+    let boxes = (
+        ErasedBox::new(nested()),
+        ThinErasedBox::new(nested()),
+        SmallErasedBox::<1>::new(nested()),
+    );
+    assert_eq!(Rc::strong_count(&rc), 1 + 3 * 20);
+    drop(boxes);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
@@ -0,0 +1,59 @@
+//! Exercises the pointer manipulation paths which are sensitive to provenance. These are ordinary
+//! tests, but are intended to also be run under Miri with strict provenance checking:
+//!
+//! ```text
+//! MIRIFLAGS=-Zmiri-strict-provenance cargo +nightly miri test --test provenance
+//! ```
+
+use core::fmt::Debug;
+
+use craft_eraser::{ErasedBox, ErasedNonNull, ErasedPtr, ThinErasedBox};
+
+#[test]
+fn test_thin_reify_box_zst() {
+    let eb = ThinErasedBox::new(());
+    let b = unsafe { eb.reify_box::<()>() };
+    assert_eq!(*b, ());
+
+    let eb = ThinErasedBox::from(Box::new([0u64; 0]) as Box<[u64]>);
+    let b = unsafe { eb.reify_box::<[u64]>() };
+    assert!(b.is_empty());
+    assert!(b.as_ptr().is_aligned());
+}
+
+#[test]
+fn test_thin_reify_box_unsized() {
+    let eb = ThinErasedBox::from(Box::new([1u16, 2, 3]) as Box<[u16]>);
+    let b = unsafe { eb.reify_box::<[u16]>() };
+    assert_eq!(*b, [1, 2, 3]);
+
+    let eb = ThinErasedBox::from(Box::new(5i32) as Box<dyn Debug>);
+    let b = unsafe { eb.reify_box::<dyn Debug>() };
+    assert_eq!(format!("{:?}", b), "5");
+}
+
+#[test]
+fn test_dangling() {
+    let ptr = ErasedNonNull::dangling::<[u32]>(0);
+    let slice = unsafe { ptr.reify_ptr::<[u32]>().as_ref() };
+    assert!(slice.is_empty());
+}
+
+#[test]
+fn test_byte_add_roundtrip() {
+    let arr = [1u32, 2, 3, 4];
+    let ptr = ErasedPtr::new(arr.as_ptr());
+
+    let far = ptr.byte_add(64);
+    let back = far.byte_offset(-56);
+    assert_eq!(unsafe { *back.reify_ref::<u32>() }, 3);
+}
+
+#[test]
+fn test_ebox_dyn_roundtrip() {
+    let eb = ErasedBox::from(Box::new(1.5f32) as Box<dyn Debug>);
+    let ptr = eb.raw_ptr();
+    let b = unsafe { eb.reify_box::<dyn Debug>() };
+    assert_eq!((&*b as *const dyn Debug).cast::<()>(), ptr.as_ptr());
+    assert_eq!(format!("{:?}", b), "1.5");
+}